@@ -0,0 +1,1123 @@
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::Id;
+
+use crate::app_state::{
+    date_selection::{ActiveColumn, DateSelection},
+    error_popup::{ErrorPopup, RetryAction},
+    function_selection::FunctionSelection,
+    insights::InsightsView,
+    loading::LoadingState,
+    log_group_selection::LogGroupSelection,
+    log_viewer::LogViewer,
+    profile_selection::ProfileSelection,
+    stream_selection::StreamSelection,
+    AppMessage, AppState, FocusedPanel,
+};
+use crate::backend::{Backend, BackendKind, LogSource};
+use crate::cli::Cli;
+use crate::recent::{RecentLimit, DEFAULT_RECENT_EVENTS};
+use crate::toml_parser::{
+    read_cache_settings, read_display_settings, read_log_settings, CacheSettings, DisplaySettings,
+    LogSettings,
+};
+use crate::ui;
+use crate::utils::time_utils::DisplayTimezone;
+
+pub struct App {
+    pub state: AppState,
+    pub profile_selection: ProfileSelection,
+    pub function_selection: Option<FunctionSelection>,
+    pub log_group_selection: Option<LogGroupSelection>,
+    pub date_selection: Option<DateSelection>,
+    pub stream_selection: Option<StreamSelection>,
+    pub log_viewer: Option<LogViewer>,
+    pub insights: Option<InsightsView>,
+    pub loading: Option<LoadingState>,
+    pub error_popup: Option<ErrorPopup>,
+    pub focused_panel: FocusedPanel,
+    pub should_quit: bool,
+    backend_kind: BackendKind,
+    backend: Option<Backend>,
+    region_override: Option<String>,
+    /// A function picked on the profile screen, opened once the profile loads.
+    pending_function: Option<String>,
+    log_settings: LogSettings,
+    display_settings: DisplaySettings,
+    cache_settings: CacheSettings,
+    /// The timezone times are shown in, switched with Ctrl+U.
+    timezone: DisplayTimezone,
+    tx: UnboundedSender<AppMessage>,
+}
+
+impl App {
+    pub fn new(cli: &Cli, tx: UnboundedSender<AppMessage>) -> Result<Self> {
+        let backend_kind = cli.backend_kind();
+        let mut profiles = backend_kind.profiles()?;
+        // Applied up front so favorites are keyed by the region actually used
+        if let Some(region) = &cli.region {
+            for profile in &mut profiles {
+                profile.region = region.clone();
+            }
+        }
+        let mut profile_selection = ProfileSelection::new(profiles);
+        if let Some(name) = &cli.profile {
+            profile_selection.select_by_name(name);
+        }
+        let mut display_settings = read_display_settings()?;
+        if let Some(timezone) = cli.timezone {
+            display_settings.timezone = timezone;
+        }
+        Ok(App {
+            state: AppState::ProfileSelection,
+            profile_selection,
+            function_selection: None,
+            log_group_selection: None,
+            date_selection: None,
+            stream_selection: None,
+            log_viewer: None,
+            insights: None,
+            loading: None,
+            error_popup: None,
+            focused_panel: FocusedPanel::Left,
+            should_quit: false,
+            backend_kind,
+            backend: None,
+            region_override: cli.region.clone(),
+            pending_function: None,
+            log_settings: read_log_settings()?,
+            timezone: display_settings.timezone,
+            display_settings,
+            cache_settings: read_cache_settings()?,
+            tx,
+        })
+    }
+
+    fn select_profile(&mut self) {
+        if let Some(mut profile) = self.profile_selection.selected_profile() {
+            if let Some(region) = &self.region_override {
+                profile.region = region.clone();
+            }
+            let backend_kind = self.backend_kind.clone();
+            let cache_ttl = Duration::minutes(self.cache_settings.function_ttl_minutes);
+            let tx = self.tx.clone();
+
+            let handle = spawn(async move {
+                let result = async {
+                    let backend = backend_kind.connect(&profile).await?;
+                    let mut function_selection =
+                        FunctionSelection::new(profile, &backend, cache_ttl);
+                    function_selection.load_functions().await?;
+                    Ok((backend, Box::new(function_selection)))
+                }
+                .await;
+                let _ = tx.send(AppMessage::ProfileLoaded(result));
+            });
+
+            self.loading = Some(LoadingState::new("Loading functions", handle));
+        }
+    }
+
+    fn enter_date_selection(&mut self) {
+        if let Some(function_selection) = self.function_selection.as_mut() {
+            let profile_name = function_selection.profile.name.clone();
+            let Some(function_name) = function_selection
+                .selected()
+                .map(|function| function.name.clone())
+            else {
+                return;
+            };
+            if let Err(e) = function_selection.record_use(&function_name) {
+                self.error_popup = Some(ErrorPopup::new(
+                    "Saving recently used functions",
+                    &e,
+                    RetryAction::SaveFunctionHistory,
+                ));
+            }
+
+            self.date_selection = Some(DateSelection::new(
+                profile_name,
+                LogSource::lambda(&function_name),
+                self.display_settings.quick_ranges.clone(),
+                self.timezone,
+            ));
+            self.state = AppState::DateSelection;
+        }
+    }
+
+    fn enter_log_group_list(&mut self) {
+        let (Some(function_selection), Some(backend)) = (&self.function_selection, &self.backend)
+        else {
+            return;
+        };
+
+        if self.log_group_selection.is_none() {
+            let mut log_group_selection =
+                LogGroupSelection::new(function_selection.profile.clone(), backend.logs.clone());
+            // A broken cache only costs us the instant list
+            let _ = log_group_selection.load_cached();
+            log_group_selection.start_loading(&self.tx);
+            self.log_group_selection = Some(log_group_selection);
+        }
+        self.state = AppState::LogGroupList;
+    }
+
+    fn enter_log_group_date_selection(&mut self) {
+        let Some(log_group_selection) = &self.log_group_selection else {
+            return;
+        };
+        let Some(log_group) = log_group_selection.selected() else {
+            return;
+        };
+
+        self.date_selection = Some(DateSelection::new(
+            log_group_selection.profile.name.clone(),
+            LogSource::log_group(&log_group.name),
+            self.display_settings.quick_ranges.clone(),
+            self.timezone,
+        ));
+        self.state = AppState::DateSelection;
+    }
+
+    fn enter_log_viewer(&mut self) {
+        if let (Some(date_selection), Some(backend)) = (&self.date_selection, &self.backend) {
+            let mut log_viewer = LogViewer::new(
+                backend.logs.clone(),
+                date_selection.source.clone(),
+                date_selection.from_date,
+                date_selection.to_date,
+            );
+            log_viewer.timezone = self.timezone;
+            log_viewer.load_pages(&self.tx, self.log_settings.background_pages);
+
+            self.log_viewer = Some(log_viewer);
+            self.state = AppState::LogViewer;
+        }
+    }
+
+    /// Opens the viewer on the latest events of the source instead of a
+    /// fixed range.
+    fn enter_recent_log_viewer(&mut self, limit: RecentLimit) {
+        if let (Some(date_selection), Some(backend)) = (&self.date_selection, &self.backend) {
+            let mut log_viewer =
+                LogViewer::recent(backend.logs.clone(), date_selection.source.clone(), limit);
+            log_viewer.timezone = self.timezone;
+            log_viewer.load_pages(&self.tx, self.log_settings.background_pages);
+
+            self.log_viewer = Some(log_viewer);
+            self.state = AppState::LogViewer;
+        }
+    }
+
+    fn enter_stream_list(&mut self) {
+        if let (Some(date_selection), Some(backend)) = (&self.date_selection, &self.backend) {
+            let mut stream_selection =
+                StreamSelection::new(date_selection.source.clone(), backend.logs.clone());
+            stream_selection.start_loading(&self.tx);
+
+            self.stream_selection = Some(stream_selection);
+            self.state = AppState::StreamList;
+        }
+    }
+
+    fn enter_stream_viewer(&mut self) {
+        let (Some(stream_selection), Some(backend)) = (&self.stream_selection, &self.backend)
+        else {
+            return;
+        };
+        let Some(stream) = stream_selection.selected() else {
+            return;
+        };
+
+        let mut log_viewer = LogViewer::for_stream(
+            backend.logs.clone(),
+            stream_selection.source.clone(),
+            stream,
+        );
+        log_viewer.timezone = self.timezone;
+        log_viewer.load_pages(&self.tx, self.log_settings.background_pages);
+
+        self.log_viewer = Some(log_viewer);
+        self.state = AppState::LogViewer;
+    }
+
+    /// Goes back to where the viewer was opened from. A range panned or
+    /// zoomed into in the viewer becomes the custom range.
+    fn close_log_viewer(&mut self) {
+        let Some(log_viewer) = self.log_viewer.take() else {
+            return;
+        };
+        if log_viewer.stream_name().is_some() {
+            self.state = AppState::StreamList;
+            return;
+        }
+        if let (true, Some(date_selection)) =
+            (log_viewer.range_changed(), self.date_selection.as_mut())
+        {
+            date_selection.set_custom_range(log_viewer.from_date, log_viewer.to_date);
+        }
+        self.state = AppState::DateSelection;
+    }
+
+    /// Switches between local time, UTC and the configured timezone on
+    /// every screen.
+    fn toggle_timezone(&mut self) {
+        self.timezone = self.timezone.next(self.display_settings.timezone);
+        if let Some(date_selection) = self.date_selection.as_mut() {
+            date_selection.timezone = self.timezone;
+        }
+        if let Some(log_viewer) = self.log_viewer.as_mut() {
+            log_viewer.timezone = self.timezone;
+            log_viewer.status_message = Some(format!("Times in {}", self.timezone.label()));
+        }
+    }
+
+    fn enter_insights(&mut self) {
+        if let (Some(date_selection), Some(backend)) = (&self.date_selection, &self.backend) {
+            self.insights = Some(InsightsView::new(
+                backend.logs.clone(),
+                date_selection.source.clone(),
+                date_selection.from_date,
+                date_selection.to_date,
+            ));
+            self.state = AppState::Insights;
+        }
+    }
+
+    /// Periodic work that runs every loop iteration, independent of input.
+    pub fn tick(&mut self) {
+        if let (AppState::LogViewer, Some(log_viewer)) = (&self.state, self.log_viewer.as_mut()) {
+            log_viewer.load_more_if_needed(&self.tx);
+            log_viewer.poll_if_due(&self.tx);
+        }
+    }
+
+    pub fn handle_message(&mut self, message: AppMessage) {
+        match message {
+            AppMessage::ProfileLoaded(result) => {
+                // A cancelled load can still deliver its result; ignore it
+                if self.loading.take().is_none() {
+                    return;
+                }
+                match result {
+                    Ok((backend, function_selection)) => {
+                        self.backend = Some(backend);
+                        let mut function_selection = *function_selection;
+                        // An outdated cache is shown while the list is fetched again
+                        if function_selection.is_stale() {
+                            function_selection.refresh(&self.tx);
+                        }
+                        self.function_selection = Some(function_selection);
+                        self.state = AppState::FunctionList;
+                        if let Some(name) = self.pending_function.take() {
+                            let found = self
+                                .function_selection
+                                .as_mut()
+                                .is_some_and(|selection| selection.select_function(&name));
+                            if found {
+                                self.enter_date_selection();
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Loading functions",
+                            &e,
+                            RetryAction::LoadFunctions,
+                        ));
+                    }
+                }
+            }
+            AppMessage::FunctionsLastLogged(result) => {
+                if let Some(function_selection) = self.function_selection.as_mut() {
+                    if let Err(e) = function_selection.handle_last_logged(result) {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Finding when functions last logged",
+                            &e,
+                            RetryAction::LoadFunctionDetails,
+                        ));
+                    }
+                }
+            }
+            AppMessage::FunctionTags(result) => {
+                if let Some(function_selection) = self.function_selection.as_mut() {
+                    if let Err(e) = function_selection.handle_tags(result) {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Loading function tags",
+                            &e,
+                            RetryAction::LoadFunctionDetails,
+                        ));
+                    }
+                }
+            }
+            AppMessage::FunctionsRefreshed(result) => {
+                if let Some(function_selection) = self.function_selection.as_mut() {
+                    if let Err(e) = function_selection.handle_functions(result, &self.tx) {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Refreshing functions",
+                            &e,
+                            RetryAction::RefreshFunctions,
+                        ));
+                    }
+                }
+            }
+            AppMessage::LogGroupsLoaded { prefix, result } => {
+                if let Some(log_group_selection) = self.log_group_selection.as_mut() {
+                    if let Err(e) = log_group_selection.handle_loaded(prefix, result) {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Loading log groups",
+                            &e,
+                            RetryAction::LoadLogGroups,
+                        ));
+                    }
+                }
+            }
+            AppMessage::LogStreamsLoaded {
+                log_group_name,
+                result,
+            } => {
+                if let Some(stream_selection) = self.stream_selection.as_mut() {
+                    if let Err(e) = stream_selection.handle_loaded(log_group_name, result) {
+                        self.error_popup = Some(ErrorPopup::new(
+                            "Loading log streams",
+                            &e,
+                            RetryAction::LoadLogStreams,
+                        ));
+                    }
+                }
+            }
+            AppMessage::StreamPage {
+                viewer_id,
+                forward,
+                token,
+                page,
+            } => {
+                if let Some(log_viewer) = self.log_viewer_with_id(viewer_id) {
+                    log_viewer.handle_stream_page(forward, token, page);
+                }
+            }
+            AppMessage::LogPage {
+                viewer_id,
+                events,
+                next_token,
+                done,
+            } => {
+                if let Some(log_viewer) = self.log_viewer_with_id(viewer_id) {
+                    log_viewer.handle_page(events, next_token, done);
+                }
+            }
+            AppMessage::RecentLogs { viewer_id, logs } => {
+                if let Some(log_viewer) = self.log_viewer_with_id(viewer_id) {
+                    log_viewer.handle_recent_logs(logs);
+                }
+            }
+            AppMessage::LogFetchFailed { viewer_id, error } => {
+                if let Some(log_viewer) = self.log_viewer_with_id(viewer_id) {
+                    log_viewer.handle_fetch_error();
+                    self.error_popup = Some(ErrorPopup::new(
+                        "Loading logs",
+                        &error,
+                        RetryAction::LoadLogs,
+                    ));
+                }
+            }
+            AppMessage::FollowEvents { viewer_id, result } => {
+                if let Some(log_viewer) = self.log_viewer_with_id(viewer_id) {
+                    log_viewer.handle_follow_events(result);
+                }
+            }
+            AppMessage::QueryStarted { view_id, query_id } => {
+                if let Some(insights) = self.insights.as_mut().filter(|i| i.id == view_id) {
+                    insights.query_id = Some(query_id);
+                }
+            }
+            AppMessage::QueryResults { view_id, results } => {
+                if let Some(insights) = self.insights.as_mut().filter(|i| i.id == view_id) {
+                    insights.handle_results(results);
+                }
+            }
+            AppMessage::QueryFailed { view_id, error } => {
+                if let Some(insights) = self.insights.as_mut().filter(|i| i.id == view_id) {
+                    insights.handle_error();
+                    self.error_popup = Some(ErrorPopup::new(
+                        "Running query",
+                        &error,
+                        RetryAction::RunQuery,
+                    ));
+                }
+            }
+            AppMessage::TaskPanicked { task_id, message } => {
+                if let Some(task_id) = task_id {
+                    self.forget_task(task_id);
+                }
+                self.error_popup = Some(ErrorPopup::new(
+                    "Background task",
+                    &anyhow!(message),
+                    RetryAction::Nothing,
+                ));
+            }
+        }
+    }
+
+    /// Drops the loading state of a task that panicked, which would
+    /// otherwise keep its screen from loading again.
+    fn forget_task(&mut self, task_id: Id) {
+        let is_task = |loading: &Option<LoadingState>| {
+            loading
+                .as_ref()
+                .is_some_and(|loading| loading.is_task(task_id))
+        };
+        let mut slots = vec![&mut self.loading];
+        if let Some(function_selection) = self.function_selection.as_mut() {
+            slots.push(&mut function_selection.loading_functions);
+            slots.push(&mut function_selection.loading_tags);
+            slots.push(&mut function_selection.loading_last_logged);
+        }
+        if let Some(log_group_selection) = self.log_group_selection.as_mut() {
+            slots.push(&mut log_group_selection.loading);
+        }
+        if let Some(stream_selection) = self.stream_selection.as_mut() {
+            slots.push(&mut stream_selection.loading);
+        }
+        for slot in slots {
+            if is_task(slot) {
+                *slot = None;
+            }
+        }
+
+        if let Some(log_viewer) = self.log_viewer.as_mut() {
+            log_viewer.handle_task_panic(task_id);
+        }
+        if let Some(insights) = self.insights.as_mut().filter(|i| is_task(&i.loading)) {
+            insights.handle_error();
+        }
+    }
+
+    fn log_viewer_with_id(&mut self, viewer_id: u64) -> Option<&mut LogViewer> {
+        self.log_viewer
+            .as_mut()
+            .filter(|log_viewer| log_viewer.id == viewer_id)
+    }
+
+    pub fn draw(&mut self, f: &mut Frame) {
+        self.draw_screen(f);
+        if let Some(popup) = &self.error_popup {
+            ui::error_popup::draw_error_popup(f, popup);
+        }
+    }
+
+    fn draw_screen(&mut self, f: &mut Frame) {
+        match self.state {
+            AppState::ProfileSelection => ui::profile_list_view::draw_profile_selection(
+                f,
+                &mut self.profile_selection,
+                self.loading.as_ref(),
+            ),
+            AppState::FunctionList => {
+                if let Some(ref mut function_selection) = self.function_selection {
+                    ui::function_list_view::draw_function_selection(
+                        f,
+                        function_selection,
+                        self.timezone,
+                    )
+                }
+            }
+            AppState::LogGroupList => {
+                if let Some(log_group_selection) = &self.log_group_selection {
+                    ui::log_group_list_view::draw_log_group_selection(f, log_group_selection);
+                }
+            }
+            AppState::DateSelection => {
+                if let Some(ref mut date_selection) = self.date_selection {
+                    ui::date_selection::draw_date_selection_panel(f, date_selection);
+                }
+            }
+            AppState::StreamList => {
+                if let (Some(stream_selection), Some(date_selection)) =
+                    (&self.stream_selection, &self.date_selection)
+                {
+                    ui::stream_list_view::draw_stream_selection(
+                        f,
+                        &date_selection.profile_name,
+                        stream_selection,
+                        self.timezone,
+                    );
+                }
+            }
+            AppState::LogViewer => {
+                if let (Some(log_viewer), Some(date_selection)) =
+                    (&self.log_viewer, &self.date_selection)
+                {
+                    ui::log_view::draw_log_view(
+                        f,
+                        date_selection,
+                        Some(log_viewer),
+                        self.focused_panel,
+                    )
+                }
+            }
+            AppState::Insights => {
+                if let (Some(insights), Some(date_selection)) =
+                    (&self.insights, &self.date_selection)
+                {
+                    ui::insights_view::draw_insights_view(f, date_selection, insights);
+                }
+            }
+        }
+    }
+
+    /// `list_height` is the number of log rows visible in the log viewer.
+    pub fn handle_key(&mut self, key: KeyEvent, list_height: usize) {
+        if self.error_popup.is_some() {
+            self.handle_error_popup_key(key);
+            return;
+        }
+        if key.code == KeyCode::Char('u') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_timezone();
+            return;
+        }
+
+        match self.state {
+            AppState::ProfileSelection => match key.code {
+                KeyCode::Char('q') => self.should_quit = true,
+                KeyCode::Esc => self.loading = None,
+                _ if self.loading.is_some() => {}
+                KeyCode::Up | KeyCode::Char('k') => self.profile_selection.previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.profile_selection.next(),
+                KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                    self.profile_selection.toggle_focus()
+                }
+                KeyCode::Enter => {
+                    self.pending_function = self.profile_selection.selected_function();
+                    self.select_profile();
+                }
+                _ => {}
+            },
+            AppState::FunctionList => {
+                if let Some(ref mut function_selection) = self.function_selection {
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        KeyCode::Esc => {
+                            self.state = AppState::ProfileSelection;
+                            self.function_selection = None;
+                            self.log_group_selection = None;
+                            // Pick up favorites and recent functions from this visit
+                            self.profile_selection.load_history();
+                        }
+                        KeyCode::Enter if function_selection.selected().is_none() => {
+                            function_selection.toggle_group();
+                        }
+                        KeyCode::Enter => {
+                            self.enter_date_selection();
+                        }
+                        KeyCode::Tab => self.enter_log_group_list(),
+                        KeyCode::Up => function_selection.previous(),
+                        KeyCode::Down => function_selection.next(),
+                        KeyCode::Left => function_selection.collapse_group(),
+                        KeyCode::Right => function_selection.expand_group(),
+                        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            function_selection.toggle_columns();
+                        }
+                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            function_selection.cycle_sort(&self.tx);
+                        }
+                        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            function_selection.cycle_group(&self.tx);
+                        }
+                        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            function_selection.refresh(&self.tx);
+                        }
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            if let Err(e) = function_selection.toggle_favorite() {
+                                self.error_popup = Some(ErrorPopup::new(
+                                    "Saving favorites",
+                                    &e,
+                                    RetryAction::SaveFunctionHistory,
+                                ));
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            function_selection.filter_input.push(c);
+                            function_selection.update_filter();
+                        }
+                        KeyCode::Backspace => {
+                            function_selection.filter_input.pop();
+                            function_selection.update_filter();
+                        }
+                        KeyCode::PageUp => {
+                            for _ in 0..10 {
+                                function_selection.previous();
+                            }
+                        }
+                        KeyCode::PageDown => {
+                            for _ in 0..10 {
+                                function_selection.next();
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            AppState::LogGroupList => {
+                if let Some(ref mut log_group_selection) = self.log_group_selection {
+                    if let Some(input) = log_group_selection.prefix_input.as_mut() {
+                        match key.code {
+                            KeyCode::Enter => log_group_selection.apply_prefix(&self.tx),
+                            KeyCode::Esc => log_group_selection.cancel_prefix(),
+                            KeyCode::Char(c) => input.push(c),
+                            KeyCode::Backspace => {
+                                input.pop();
+                            }
+                            _ => {}
+                        }
+                        return;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        KeyCode::Esc | KeyCode::Tab => self.state = AppState::FunctionList,
+                        KeyCode::Enter => self.enter_log_group_date_selection(),
+                        KeyCode::Up => log_group_selection.previous(),
+                        KeyCode::Down => log_group_selection.next(),
+                        KeyCode::PageUp => {
+                            for _ in 0..10 {
+                                log_group_selection.previous();
+                            }
+                        }
+                        KeyCode::PageDown => {
+                            for _ in 0..10 {
+                                log_group_selection.next();
+                            }
+                        }
+                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_group_selection.edit_prefix();
+                        }
+                        KeyCode::Char(c) => {
+                            log_group_selection.filter_input.push(c);
+                            log_group_selection.update_filter();
+                        }
+                        KeyCode::Backspace => {
+                            log_group_selection.filter_input.pop();
+                            log_group_selection.update_filter();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            AppState::DateSelection => {
+                if let Some(ref mut date_selection) = self.date_selection {
+                    if date_selection.range_input.is_some() {
+                        match key.code {
+                            KeyCode::Enter if date_selection.apply_range_input() => {
+                                match date_selection.recent {
+                                    Some(limit) => self.enter_recent_log_viewer(limit),
+                                    None => self.enter_log_viewer(),
+                                }
+                            }
+                            KeyCode::Esc => date_selection.cancel_range_input(),
+                            KeyCode::Char(c) => date_selection.push_range_char(c),
+                            KeyCode::Backspace => date_selection.pop_range_char(),
+                            _ => {}
+                        }
+                        return;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        KeyCode::Esc => {
+                            // Back to the list the source was picked from
+                            self.state = if date_selection.source.function_name.is_some() {
+                                AppState::FunctionList
+                            } else {
+                                AppState::LogGroupList
+                            };
+                            self.date_selection = None;
+                        }
+                        KeyCode::Char('c') => date_selection.toggle_custom(),
+                        KeyCode::Tab
+                            if date_selection.active_column == ActiveColumn::CustomRange =>
+                        {
+                            date_selection.toggle_selection()
+                        }
+                        KeyCode::Char('1') => {
+                            date_selection.select_column(ActiveColumn::QuickRanges)
+                        }
+                        KeyCode::Char('2') => {
+                            date_selection.select_column(ActiveColumn::CustomRange)
+                        }
+                        KeyCode::Left => {
+                            if date_selection.custom_selection {
+                                date_selection.previous_field()
+                            } else {
+                                date_selection.previous_quick_range()
+                            }
+                        }
+                        KeyCode::Right => {
+                            if date_selection.custom_selection {
+                                date_selection.next_field()
+                            } else {
+                                date_selection.next_quick_range()
+                            }
+                        }
+                        KeyCode::Up => {
+                            if date_selection.custom_selection {
+                                date_selection.adjust_current_field(true)
+                            } else {
+                                date_selection.previous_quick_range();
+                            }
+                        }
+                        KeyCode::Down => {
+                            if date_selection.custom_selection {
+                                date_selection.adjust_current_field(false)
+                            } else {
+                                date_selection.next_quick_range();
+                            }
+                        }
+                        KeyCode::Enter | KeyCode::Char('i')
+                            if !date_selection.resolve_quick_range() => {}
+                        KeyCode::Enter => match date_selection.recent {
+                            Some(limit) => self.enter_recent_log_viewer(limit),
+                            None => self.enter_log_viewer(),
+                        },
+                        KeyCode::Char('r') => {
+                            self.enter_recent_log_viewer(RecentLimit::Events(DEFAULT_RECENT_EVENTS))
+                        }
+                        KeyCode::Char('i') => self.enter_insights(),
+                        KeyCode::Char('s') => self.enter_stream_list(),
+                        KeyCode::Char('/') => date_selection.start_range_input(),
+                        _ => {}
+                    }
+                }
+            }
+            AppState::StreamList => {
+                if let Some(ref mut stream_selection) = self.stream_selection {
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        KeyCode::Esc => {
+                            self.state = AppState::DateSelection;
+                            self.stream_selection = None;
+                        }
+                        KeyCode::Enter => self.enter_stream_viewer(),
+                        KeyCode::Up => stream_selection.previous(),
+                        KeyCode::Down => stream_selection.next(),
+                        KeyCode::PageUp => {
+                            for _ in 0..list_height {
+                                stream_selection.previous();
+                            }
+                        }
+                        KeyCode::PageDown => {
+                            for _ in 0..list_height {
+                                stream_selection.next();
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            stream_selection.filter_input.push(c);
+                            stream_selection.update_filter();
+                        }
+                        KeyCode::Backspace => {
+                            stream_selection.filter_input.pop();
+                            stream_selection.update_filter();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            AppState::LogViewer => {
+                if let Some(ref mut log_viewer) = self.log_viewer {
+                    if log_viewer.export_menu.is_some() {
+                        match key.code {
+                            KeyCode::Up => log_viewer.previous_export_format(),
+                            KeyCode::Down => log_viewer.next_export_format(),
+                            KeyCode::Enter => log_viewer.export_selected(),
+                            KeyCode::Esc => log_viewer.close_export_menu(),
+                            _ => {}
+                        }
+                        return;
+                    }
+                    if let Some(input) = log_viewer.server_filter_input.as_mut() {
+                        match key.code {
+                            KeyCode::Enter => log_viewer
+                                .apply_server_filter(&self.tx, self.log_settings.background_pages),
+                            KeyCode::Esc => log_viewer.cancel_server_filter(),
+                            KeyCode::Char(c) => input.push(c),
+                            KeyCode::Backspace => {
+                                input.pop();
+                            }
+                            _ => {}
+                        }
+                        return;
+                    }
+                    if log_viewer.timeline_cursor.is_some() {
+                        match key.code {
+                            KeyCode::Left => log_viewer.move_timeline_cursor(-1),
+                            KeyCode::Right => log_viewer.move_timeline_cursor(1),
+                            KeyCode::Home => log_viewer.move_timeline_cursor(isize::MIN),
+                            KeyCode::End => log_viewer.move_timeline_cursor(isize::MAX),
+                            KeyCode::Enter => log_viewer.jump_to_bucket(),
+                            KeyCode::Char('z') => log_viewer
+                                .zoom_to_bucket(&self.tx, self.log_settings.background_pages),
+                            KeyCode::Esc => log_viewer.toggle_timeline(),
+                            KeyCode::Char('q') => self.should_quit = true,
+                            _ => {}
+                        }
+                        return;
+                    }
+                    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+                    if let (false, false, Some(invocations)) = (
+                        log_viewer.expanded,
+                        control,
+                        log_viewer.invocations.as_mut(),
+                    ) {
+                        let page = list_height as isize;
+                        let handled = match key.code {
+                            KeyCode::Up => {
+                                invocations.move_selection(-1);
+                                true
+                            }
+                            KeyCode::Down => {
+                                invocations.move_selection(1);
+                                true
+                            }
+                            KeyCode::PageUp => {
+                                invocations.move_selection(-page);
+                                true
+                            }
+                            KeyCode::PageDown => {
+                                invocations.move_selection(page);
+                                true
+                            }
+                            KeyCode::Right => {
+                                invocations.expand();
+                                true
+                            }
+                            KeyCode::Left => {
+                                invocations.collapse();
+                                true
+                            }
+                            // Enter on an event opens its details like in the event list
+                            KeyCode::Enter if !invocations.is_event_selected() => {
+                                invocations.toggle_expand();
+                                true
+                            }
+                            _ => false,
+                        };
+                        if handled {
+                            return;
+                        }
+                    }
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        // The first Esc cancels an in-flight fetch, the next leaves the
+                        // request's lines, the last one goes back
+                        KeyCode::Esc
+                            if !log_viewer.cancel_loading()
+                                && !log_viewer.clear_request_filter() =>
+                        {
+                            self.close_log_viewer()
+                        }
+                        // Pan and zoom the range
+                        KeyCode::Left if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.shift_range(
+                                false,
+                                &self.tx,
+                                self.log_settings.background_pages,
+                            );
+                        }
+                        KeyCode::Right if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.shift_range(
+                                true,
+                                &self.tx,
+                                self.log_settings.background_pages,
+                            );
+                        }
+                        KeyCode::Up if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.zoom(true, &self.tx, self.log_settings.background_pages);
+                        }
+                        KeyCode::Down if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.zoom(false, &self.tx, self.log_settings.background_pages);
+                        }
+                        KeyCode::Up => {
+                            if log_viewer.expanded {
+                                log_viewer.scroll_up();
+                            } else {
+                                log_viewer.move_selection(-1, list_height);
+                            }
+                        }
+                        KeyCode::Down => {
+                            if log_viewer.expanded {
+                                log_viewer.scroll_down();
+                            } else {
+                                log_viewer.move_selection(1, list_height);
+                            }
+                        }
+                        KeyCode::Enter => {
+                            log_viewer.toggle_expand();
+                            log_viewer.scroll_position = 0; // Reset scroll position when toggling
+                        }
+                        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.open_export_menu();
+                        }
+                        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.toggle_follow();
+                        }
+                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.edit_server_filter();
+                        }
+                        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.load_pages(&self.tx, self.log_settings.background_pages);
+                        }
+                        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.toggle_invocations();
+                        }
+                        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.show_request();
+                        }
+                        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.toggle_stats();
+                        }
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.cycle_problem_filter();
+                        }
+                        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.toggle_timeline();
+                        }
+                        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            log_viewer.show_around_event(
+                                self.log_settings.around_minutes,
+                                &self.tx,
+                                self.log_settings.background_pages,
+                            );
+                        }
+                        KeyCode::Char(c) if !log_viewer.expanded => {
+                            log_viewer.filter_input.push(c);
+                            log_viewer.update_filter();
+                        }
+                        KeyCode::Backspace if !log_viewer.expanded => {
+                            log_viewer.filter_input.pop();
+                            log_viewer.update_filter();
+                        }
+                        KeyCode::PageUp => {
+                            if log_viewer.expanded {
+                                log_viewer.scroll_position =
+                                    log_viewer.scroll_position.saturating_sub(10);
+                            } else {
+                                log_viewer.page_up();
+                            }
+                        }
+                        KeyCode::PageDown => {
+                            if log_viewer.expanded {
+                                log_viewer.scroll_position =
+                                    log_viewer.scroll_position.saturating_add(10);
+                            } else {
+                                log_viewer.page_down();
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Insights => {
+                if let Some(ref mut insights) = self.insights {
+                    if insights.editing {
+                        match key.code {
+                            KeyCode::Enter => insights.run_query(&self.tx),
+                            KeyCode::Esc if insights.results.is_some() => insights.editing = false,
+                            KeyCode::Esc => {
+                                self.state = AppState::DateSelection;
+                                self.insights = None;
+                            }
+                            KeyCode::Char(c) => insights.query_input.push(c),
+                            KeyCode::Backspace => {
+                                insights.query_input.pop();
+                            }
+                            _ => {}
+                        }
+                        return;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') => self.should_quit = true,
+                        // The first Esc cancels a running query, the next goes back
+                        KeyCode::Esc if !insights.cancel_query() => {
+                            self.state = AppState::DateSelection;
+                            self.insights = None;
+                        }
+                        KeyCode::Char('e') | KeyCode::Char('/') => insights.editing = true,
+                        KeyCode::Enter => insights.run_query(&self.tx),
+                        KeyCode::Up | KeyCode::Char('k') => insights.previous_row(),
+                        KeyCode::Down | KeyCode::Char('j') => insights.next_row(),
+                        KeyCode::PageUp => insights.page_up(list_height),
+                        KeyCode::PageDown => insights.page_down(list_height),
+                        KeyCode::Left => insights.previous_sort_column(),
+                        KeyCode::Right => insights.next_sort_column(),
+                        KeyCode::Char('s') => insights.toggle_sort_order(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_error_popup_key(&mut self, key: KeyEvent) {
+        let Some(popup) = self.error_popup.take() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('r') if popup.can_retry() => match popup.retry {
+                RetryAction::LoadFunctions => self.select_profile(),
+                RetryAction::LoadFunctionDetails => {
+                    if let Some(function_selection) = self.function_selection.as_mut() {
+                        function_selection.load_details(&self.tx);
+                    }
+                }
+                RetryAction::RefreshFunctions => {
+                    if let Some(function_selection) = self.function_selection.as_mut() {
+                        function_selection.refresh(&self.tx);
+                    }
+                }
+                RetryAction::SaveFunctionHistory => {
+                    if let Some(function_selection) = &self.function_selection {
+                        if let Err(e) = function_selection.save_history() {
+                            self.error_popup = Some(ErrorPopup::new(
+                                "Saving favorites",
+                                &e,
+                                RetryAction::SaveFunctionHistory,
+                            ));
+                        }
+                    }
+                }
+                RetryAction::LoadLogGroups => {
+                    if let Some(log_group_selection) = self.log_group_selection.as_mut() {
+                        log_group_selection.start_loading(&self.tx);
+                    }
+                }
+                RetryAction::LoadLogStreams => {
+                    if let Some(stream_selection) = self.stream_selection.as_mut() {
+                        stream_selection.start_loading(&self.tx);
+                    }
+                }
+                RetryAction::LoadLogs => {
+                    if let Some(log_viewer) = self.log_viewer.as_mut() {
+                        log_viewer.load_pages(&self.tx, self.log_settings.background_pages);
+                    }
+                }
+                RetryAction::RunQuery => {
+                    if let Some(insights) = self.insights.as_mut() {
+                        insights.run_query(&self.tx);
+                    }
+                }
+                RetryAction::Nothing => {}
+            },
+            KeyCode::Esc => {
+                if popup.retry == RetryAction::LoadLogs {
+                    self.close_log_viewer();
+                }
+            }
+            KeyCode::Enter => {}
+            _ => self.error_popup = Some(popup),
+        }
+    }
+}
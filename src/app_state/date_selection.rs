@@ -1,5 +1,10 @@
 use chrono::{DateTime, Datelike, Duration, Local};
 
+use crate::backend::LogSource;
+use crate::recent::RecentLimit;
+use crate::toml_parser::{DisplaySettings, QuickRange};
+use crate::utils::time_utils::DisplayTimezone;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum DateField {
     Year,
@@ -9,63 +14,10 @@ pub enum DateField {
     Minute,
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum QuickRange {
-    LastHour,
-    Last2Hours,
-    Last3Hours,
-    Last6Hours,
-    Last12Hours,
-    Last24Hours,
-    Last3Days,
-    LastWeek,
-}
-
-impl QuickRange {
-    pub fn all() -> Vec<QuickRange> {
-        vec![
-            QuickRange::LastHour,
-            QuickRange::Last2Hours,
-            QuickRange::Last3Hours,
-            QuickRange::Last6Hours,
-            QuickRange::Last12Hours,
-            QuickRange::Last24Hours,
-            QuickRange::Last3Days,
-            QuickRange::LastWeek,
-        ]
-    }
-
-    pub fn to_duration(&self) -> Duration {
-        match self {
-            QuickRange::LastHour => Duration::hours(1),
-            QuickRange::Last2Hours => Duration::hours(2),
-            QuickRange::Last3Hours => Duration::hours(3),
-            QuickRange::Last6Hours => Duration::hours(6),
-            QuickRange::Last12Hours => Duration::hours(12),
-            QuickRange::Last24Hours => Duration::hours(24),
-            QuickRange::Last3Days => Duration::days(3),
-            QuickRange::LastWeek => Duration::days(7),
-        }
-    }
-
-    pub fn display_name(&self) -> &str {
-        match self {
-            QuickRange::LastHour => "Last Hour",
-            QuickRange::Last2Hours => "Last 2 Hours",
-            QuickRange::Last3Hours => "Last 3 Hours",
-            QuickRange::Last6Hours => "Last 6 Hours",
-            QuickRange::Last12Hours => "Last 12 Hours",
-            QuickRange::Last24Hours => "Last 24 Hours",
-            QuickRange::Last3Days => "Last 3 Days",
-            QuickRange::LastWeek => "Last Week",
-        }
-    }
-}
-
 #[derive(Debug)]
 pub struct DateSelection {
     pub profile_name: String,
-    pub function_name: String,
+    pub source: LogSource,
     pub from_date: DateTime<Local>,
     pub to_date: DateTime<Local>,
     pub is_selecting_from: bool,
@@ -74,40 +26,63 @@ pub struct DateSelection {
     pub selected_quick_range: Option<usize>,
     pub custom_selection: bool,
     pub active_column: ActiveColumn,
+    /// A range expression being typed, e.g. `2h ago..now`.
+    pub range_input: Option<String>,
+    pub range_error: Option<String>,
+    /// Dates are shown and edited in this timezone.
+    pub timezone: DisplayTimezone,
+    /// Set when the latest events were asked for instead of a range, e.g.
+    /// with a `recent 100` quick range.
+    pub recent: Option<RecentLimit>,
 }
 
 impl Default for DateSelection {
     fn default() -> Self {
         Self {
             profile_name: String::new(),
-            function_name: String::new(),
+            source: LogSource::default(),
             from_date: Local::now() - Duration::hours(1),
             to_date: Local::now(),
             is_selecting_from: true,
             current_field: DateField::Day,
-            quick_ranges: QuickRange::all(),
+            quick_ranges: DisplaySettings::default().quick_ranges,
             selected_quick_range: Some(0),
             custom_selection: false,
             active_column: ActiveColumn::QuickRanges,
+            range_input: None,
+            range_error: None,
+            timezone: DisplayTimezone::Local,
+            recent: None,
         }
     }
 }
 
 impl DateSelection {
-    pub fn new(profile_name: String, function_name: String) -> Self {
+    pub fn new(
+        profile_name: String,
+        source: LogSource,
+        quick_ranges: Vec<QuickRange>,
+        timezone: DisplayTimezone,
+    ) -> Self {
         let now = Local::now();
-        Self {
+        let mut date_selection = Self {
             profile_name,
-            function_name,
+            source,
             from_date: now - Duration::hours(1),
             to_date: now,
             is_selecting_from: true,
             current_field: DateField::Day,
-            quick_ranges: QuickRange::all(),
+            quick_ranges,
             selected_quick_range: Some(0),
             custom_selection: false,
             active_column: ActiveColumn::QuickRanges,
-        }
+            range_input: None,
+            range_error: None,
+            timezone,
+            recent: None,
+        };
+        date_selection.apply_quick_range(0);
+        date_selection
     }
 
     pub fn toggle_selection(&mut self) {
@@ -142,10 +117,37 @@ impl DateSelection {
         }
     }
 
+    /// Resolves the selected quick range against the current time again, as
+    /// it's about to be used. Returns `false` if it can't be used now, with
+    /// the reason in `range_error`.
+    pub fn resolve_quick_range(&mut self) -> bool {
+        if let (false, Some(index)) = (self.custom_selection, self.selected_quick_range) {
+            self.apply_quick_range(index);
+        }
+        self.range_error.is_none()
+    }
+
     fn apply_quick_range(&mut self, index: usize) {
         if let Some(range) = self.quick_ranges.get(index) {
-            self.to_date = Local::now();
-            self.from_date = self.to_date - range.to_duration();
+            self.recent = None;
+            self.range_error = None;
+            if let Some(limit) = RecentLimit::parse(&range.range) {
+                match limit {
+                    Ok(limit) => self.recent = Some(limit),
+                    Err(e) => self.range_error = Some(format!("{}: {}", range.display_name(), e)),
+                }
+                return;
+            }
+            // Presets like `today` are evaluated when picked, in the display timezone
+            match self.timezone.parse_time_range(&range.range, Local::now()) {
+                Ok((from_date, to_date)) => {
+                    self.from_date = from_date;
+                    self.to_date = to_date;
+                }
+                Err(e) => {
+                    self.range_error = Some(format!("{}: {}", range.display_name(), e));
+                }
+            }
         }
     }
 
@@ -177,15 +179,22 @@ impl DateSelection {
         };
 
         match self.current_field {
+            // Years and months change as seen in the display timezone
             DateField::Year => {
                 let years = if increment { 1 } else { -1 };
-                *date = date.with_year(date.year() + years).unwrap_or(*date);
+                *date = self
+                    .timezone
+                    .map_wall_time(*date, |time| time.with_year(time.year() + years))
+                    .unwrap_or(*date);
             }
             DateField::Month => {
                 let months = if increment { 1 } else { -1 };
-                let new_month = (date.month() as i32 + months).rem_euclid(12) as u32;
-                *date = date
-                    .with_month(if new_month == 0 { 12 } else { new_month })
+                *date = self
+                    .timezone
+                    .map_wall_time(*date, |time| {
+                        let new_month = (time.month() as i32 + months).rem_euclid(12) as u32;
+                        time.with_month(if new_month == 0 { 12 } else { new_month })
+                    })
                     .unwrap_or(*date);
             }
             DateField::Day => {
@@ -210,37 +219,89 @@ impl DateSelection {
         }
     }
 
-    pub fn switch_column(&mut self, column: ActiveColumn) {
-        self.active_column = column.clone();
-        match column {
-            ActiveColumn::QuickRanges => {
-                self.custom_selection = false;
+    pub fn start_range_input(&mut self) {
+        self.range_input = Some(String::new());
+        self.range_error = None;
+    }
+
+    pub fn cancel_range_input(&mut self) {
+        self.range_input = None;
+        self.range_error = None;
+    }
+
+    pub fn push_range_char(&mut self, c: char) {
+        if let Some(input) = self.range_input.as_mut() {
+            input.push(c);
+            self.range_error = None;
+        }
+    }
+
+    pub fn pop_range_char(&mut self) {
+        if let Some(input) = self.range_input.as_mut() {
+            input.pop();
+            self.range_error = None;
+        }
+    }
+
+    /// Applies the typed expression as the custom range, or as `recent`
+    /// for `recent 100`. On error the input stays open with the reason in
+    /// `range_error`.
+    pub fn apply_range_input(&mut self) -> bool {
+        let Some(input) = &self.range_input else {
+            return false;
+        };
+        if let Some(limit) = RecentLimit::parse(input) {
+            return match limit {
+                Ok(limit) => {
+                    self.cancel_range_input();
+                    self.recent = Some(limit);
+                    true
+                }
+                Err(e) => {
+                    self.range_error = Some(e.to_string());
+                    false
+                }
+            };
+        }
+        match self.timezone.parse_time_range(input, Local::now()) {
+            Ok((from_date, to_date)) => {
+                self.set_custom_range(from_date, to_date);
+                self.cancel_range_input();
+                true
             }
-            ActiveColumn::CustomRange => {
-                self.custom_selection = true;
+            Err(e) => {
+                self.range_error = Some(e.to_string());
+                false
             }
         }
     }
 
+    /// Shows a range picked elsewhere, e.g. zoomed into in the log viewer, as
+    /// the custom range.
+    pub fn set_custom_range(&mut self, from_date: DateTime<Local>, to_date: DateTime<Local>) {
+        self.recent = None;
+        self.from_date = from_date;
+        self.to_date = to_date;
+        self.select_column(ActiveColumn::CustomRange);
+    }
+
     pub fn select_column(&mut self, column: ActiveColumn) {
         self.active_column = column.clone();
         match column {
             ActiveColumn::QuickRanges => {
                 self.custom_selection = false;
+                self.recent = self
+                    .selected_quick_range
+                    .and_then(|i| self.quick_ranges.get(i))
+                    .and_then(|range| RecentLimit::parse(&range.range))
+                    .and_then(Result::ok);
             }
             ActiveColumn::CustomRange => {
                 self.custom_selection = true;
+                self.recent = None;
             }
         }
     }
-
-    pub fn select_from(&mut self) {
-        self.is_selecting_from = true;
-    }
-
-    pub fn select_to(&mut self) {
-        self.is_selecting_from = false;
-    }
 }
 
 // Add this near the top of the file with your other enums
@@ -249,10 +310,3 @@ pub enum ActiveColumn {
     QuickRanges,
     CustomRange,
 }
-
-// Make sure you have this enum defined
-#[derive(PartialEq)]
-pub enum ActiveField {
-    From,
-    To,
-}
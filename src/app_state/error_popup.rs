@@ -0,0 +1,38 @@
+use crate::error::AwsError;
+
+/// What pressing `r` on the error popup re-runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryAction {
+    LoadFunctions,
+    LoadFunctionDetails,
+    RefreshFunctions,
+    SaveFunctionHistory,
+    LoadLogGroups,
+    LoadLogStreams,
+    LoadLogs,
+    RunQuery,
+    /// The failure can't be repeated on purpose, e.g. a panic.
+    Nothing,
+}
+
+#[derive(Debug)]
+pub struct ErrorPopup {
+    /// What was being done when the error happened, e.g. "Loading logs"
+    pub context: String,
+    pub error: AwsError,
+    pub retry: RetryAction,
+}
+
+impl ErrorPopup {
+    pub fn new(context: &str, error: &anyhow::Error, retry: RetryAction) -> Self {
+        Self {
+            context: context.to_string(),
+            error: AwsError::from_anyhow(error),
+            retry,
+        }
+    }
+
+    pub fn can_retry(&self) -> bool {
+        self.retry != RetryAction::Nothing && self.error.is_retryable()
+    }
+}
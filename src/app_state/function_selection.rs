@@ -1,202 +1,913 @@
 use anyhow::Result;
-use aws_config::{BehaviorVersion, Region};
-use aws_sdk_lambda::Client as LambdaClient;
-use ratatui::widgets::ListState;
-use std::sync::{Arc, Mutex};
+use chrono::{Duration, Utc};
+use ratatui::widgets::TableState;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
 use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinSet;
 
+use crate::app_state::{loading::LoadingState, AppMessage};
+use crate::backend::{lambda_log_group, Backend, FunctionCatalog, FunctionInfo, LogBackend};
+use crate::error::{AwsError, AwsErrorKind};
+use crate::fuzzy::{FuzzyMatch, FuzzyQuery};
 use crate::toml_parser::Profile;
-use crate::utils::file_utils::{cache_functions, load_cached_functions};
+use crate::utils::file_utils::{
+    cache_functions, load_cached_functions, load_function_history, save_function_history,
+    FunctionHistory,
+};
+
+/// How many per-function requests run at once when loading tags or log activity.
+const CONCURRENT_REQUESTS: usize = 8;
+
+// Pinned sections above the list, shown while there is no filter
+const FAVORITES: &str = "★ Favorites";
+const RECENTLY_USED: &str = "Recently used";
+const ALL_FUNCTIONS: &str = "All functions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortBy {
+    #[default]
+    Name,
+    LastModified,
+    Runtime,
+    Memory,
+    LastLogged,
+}
+
+impl SortBy {
+    pub fn next(self) -> Self {
+        match self {
+            SortBy::Name => SortBy::LastModified,
+            SortBy::LastModified => SortBy::Runtime,
+            SortBy::Runtime => SortBy::Memory,
+            SortBy::Memory => SortBy::LastLogged,
+            SortBy::LastLogged => SortBy::Name,
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            SortBy::Name => "Name",
+            SortBy::LastModified => "Last modified",
+            SortBy::Runtime => "Runtime",
+            SortBy::Memory => "Memory",
+            SortBy::LastLogged => "Last logged",
+        }
+    }
+}
+
+/// How the list is split into collapsible groups.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum GroupBy {
+    #[default]
+    None,
+    /// The first segments of `<service>-<stage>-<handler>` style names.
+    Prefix(usize),
+    /// The value of a tag; `None` takes the first tag key once tags are loaded.
+    Tag(Option<String>),
+}
+
+impl GroupBy {
+    pub fn display_name(&self) -> String {
+        match self {
+            GroupBy::None => "None".to_string(),
+            GroupBy::Prefix(1) => "Name prefix".to_string(),
+            GroupBy::Prefix(segments) => format!("Name prefix ({} parts)", segments),
+            GroupBy::Tag(Some(key)) => format!("Tag {}", key),
+            GroupBy::Tag(None) => "Tag".to_string(),
+        }
+    }
+
+    /// Where functions without a group are listed, after every group.
+    fn ungrouped_name(&self) -> &'static str {
+        match self {
+            GroupBy::Tag(_) => "(untagged)",
+            _ => "(other)",
+        }
+    }
+}
+
+/// One line of the list: a group header or a function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionRow {
+    Group {
+        name: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// Index into `filtered_functions`.
+    Function(usize),
+    /// A favorite or recently used function in a pinned section; index into
+    /// `filtered_functions`.
+    Pinned(usize),
+}
 
 #[derive(Debug)]
 pub struct FunctionSelection {
     pub profile: Profile,
-    pub lambda_functions: Arc<Mutex<Vec<String>>>,
-    pub filtered_functions: Vec<String>,
+    pub lambda_functions: Vec<FunctionInfo>,
+    /// When `lambda_functions` were listed, in epoch millis.
+    pub fetched_at: Option<i64>,
+    /// How long a listing is used before it's refreshed.
+    cache_ttl: Duration,
+    /// The background refresh of the function list.
+    pub loading_functions: Option<LoadingState>,
+    /// The functions matching the filter, in list order.
+    pub filtered_functions: Vec<FunctionInfo>,
+    /// How each function matched the filter, by name; empty without a filter.
+    pub matches: HashMap<String, FuzzyMatch>,
+    pub rows: Vec<FunctionRow>,
+    /// Index into `rows`.
     pub selected_index: usize,
     pub filter_input: String,
-    pub list_state: ListState,
+    pub table_state: TableState,
+    /// Shows runtime, memory, timeout and architecture columns in the list.
+    pub show_columns: bool,
+    pub sort_by: SortBy,
+    pub group_by: GroupBy,
+    collapsed: HashSet<String>,
+    /// Tags by function name, loaded the first time the list is grouped by tag.
+    pub tags: Option<HashMap<String, BTreeMap<String, String>>>,
+    /// The latest event time by function name, loaded the first time the
+    /// list is sorted by it. Functions that never logged are missing.
+    pub last_logged: Option<HashMap<String, i64>>,
+    pub loading_tags: Option<LoadingState>,
+    pub loading_last_logged: Option<LoadingState>,
+    pub history: FunctionHistory,
+    catalog: Arc<dyn FunctionCatalog>,
+    logs: Arc<dyn LogBackend>,
 }
 
 impl FunctionSelection {
-    pub fn new(profile: Profile) -> Self {
+    pub fn new(profile: Profile, backend: &Backend, cache_ttl: Duration) -> Self {
         Self {
             profile,
-            lambda_functions: Arc::new(Mutex::new(Vec::new())),
+            lambda_functions: Vec::new(),
+            fetched_at: None,
+            cache_ttl,
+            loading_functions: None,
             filtered_functions: Vec::new(),
+            matches: HashMap::new(),
+            rows: Vec::new(),
             selected_index: 0,
             filter_input: String::new(),
-            list_state: ListState::default(),
+            table_state: TableState::default(),
+            show_columns: false,
+            sort_by: SortBy::default(),
+            group_by: GroupBy::default(),
+            collapsed: HashSet::new(),
+            tags: None,
+            last_logged: None,
+            loading_tags: None,
+            loading_last_logged: None,
+            history: FunctionHistory::default(),
+            catalog: Arc::clone(&backend.functions),
+            logs: Arc::clone(&backend.logs),
         }
     }
 
     pub async fn load_functions(&mut self) -> Result<()> {
-        // Try to load from cache first
-        if let Some(cached_functions) =
-            load_cached_functions(&self.profile.name, &self.profile.region)?
-        {
-            // Update UI immediately with cached data
-            self.lambda_functions.lock().unwrap().clear();
-            self.lambda_functions
-                .lock()
-                .unwrap()
-                .extend(cached_functions);
-            self.filtered_functions = self.lambda_functions.lock().unwrap().clone();
-            self.list_state.select(Some(0));
-
-            // Clone necessary data for background task
-            let profile_name = self.profile.name.clone();
-            let profile_region = self.profile.region.clone();
-            let lambda_functions = Arc::clone(&self.lambda_functions);
-
-            // Spawn background task to update cache
-            spawn(async move {
-                if let Err(e) = update_functions_in_background(
-                    profile_name.clone(),
-                    profile_region.clone(),
-                    lambda_functions,
-                )
-                .await
-                {
-                    eprintln!("Background update failed: {}", e);
-                }
-            });
+        // A damaged history file shouldn't keep the functions from loading
+        self.history =
+            load_function_history(&self.profile.name, &self.profile.region).unwrap_or_default();
 
+        if let Some(cache) = load_cached_functions(&self.profile.name, &self.profile.region)? {
+            self.lambda_functions = cache.functions;
+            self.fetched_at = Some(cache.fetched_at);
+            self.update_filter();
             return Ok(());
         }
 
-        // If no cache exists, load directly from AWS
-        self.load_functions_from_aws().await
+        let functions = list_functions(Arc::clone(&self.catalog)).await?;
+        let fetched_at = Utc::now().timestamp_millis();
+        cache_functions(
+            &self.profile.name,
+            &self.profile.region,
+            fetched_at,
+            &functions,
+        )?;
+        self.lambda_functions = functions;
+        self.fetched_at = Some(fetched_at);
+        self.update_filter();
+        Ok(())
     }
 
-    async fn load_functions_from_aws(&mut self) -> Result<()> {
-        let config = aws_config::defaults(BehaviorVersion::latest())
-            .profile_name(&self.profile.name)
-            .region(Region::new(self.profile.region.clone()))
-            .load()
-            .await;
+    /// Whether the listing is older than the cache TTL.
+    pub fn is_stale(&self) -> bool {
+        self.fetched_at.is_none_or(|fetched_at| {
+            Utc::now().timestamp_millis() - fetched_at > self.cache_ttl.num_milliseconds()
+        })
+    }
 
-        let client = LambdaClient::new(&config);
-        let mut functions = Vec::new();
-        let mut next_marker = None;
+    /// Lists the functions again in the background; the result arrives as
+    /// `AppMessage::FunctionsRefreshed`.
+    pub fn refresh(&mut self, tx: &UnboundedSender<AppMessage>) {
+        let catalog = Arc::clone(&self.catalog);
+        let tx = tx.clone();
+        let handle = spawn(async move {
+            let result = list_functions(catalog).await;
+            let _ = tx.send(AppMessage::FunctionsRefreshed(result));
+        });
+        self.loading_functions = Some(LoadingState::new("Refreshing functions", handle));
+    }
 
-        loop {
-            let mut request = client.list_functions();
-            if let Some(marker) = next_marker {
-                request = request.marker(marker);
-            }
+    /// Applies a refreshed listing and caches it, keeping the filter and the
+    /// selection; a cancelled refresh is ignored.
+    pub fn handle_functions(
+        &mut self,
+        result: Result<Vec<FunctionInfo>>,
+        tx: &UnboundedSender<AppMessage>,
+    ) -> Result<()> {
+        if self.loading_functions.take().is_none() {
+            return Ok(());
+        }
+        let functions = result?;
+        let fetched_at = Utc::now().timestamp_millis();
 
-            let response = request.send().await?;
-            let function_list = response.functions();
-            for function in function_list {
-                if let Some(name) = &function.function_name {
-                    functions.push(name.clone())
-                }
-            }
+        // Tags and log activity may have changed too; reload what is shown
+        self.tags = None;
+        self.last_logged = None;
+        self.loading_tags = None;
+        self.loading_last_logged = None;
+        self.replace_functions(functions, fetched_at);
+        self.load_details(tx);
 
-            next_marker = response.next_marker().map(String::from);
-            if next_marker.is_none() {
-                break;
-            }
-        }
+        cache_functions(
+            &self.profile.name,
+            &self.profile.region,
+            fetched_at,
+            &self.lambda_functions,
+        )
+    }
 
-        functions.sort();
+    fn replace_functions(&mut self, functions: Vec<FunctionInfo>, fetched_at: i64) {
+        self.lambda_functions = functions;
+        self.fetched_at = Some(fetched_at);
 
-        // Cache the functions
-        cache_functions(&self.profile.name, &self.profile.region, &functions)?;
+        // Row indices change with the new list; find the selection by name
+        let selected = self.selected_key();
+        self.apply_filter();
+        self.arrange();
+        self.reselect(selected);
+    }
 
-        self.lambda_functions.lock().unwrap().clear();
-        self.lambda_functions.lock().unwrap().extend(functions);
-        self.filtered_functions = self.lambda_functions.lock().unwrap().clone();
-        self.list_state.select(Some(0));
-        Ok(())
+    pub fn update_filter(&mut self) {
+        self.apply_filter();
+        self.arrange();
+        self.select(0);
     }
 
-    pub async fn update_filter(&mut self) -> Result<()> {
-        let lambda_functions = self.lambda_functions.lock().unwrap().clone();
+    /// Narrows the functions down to the ones matching the filter.
+    fn apply_filter(&mut self) {
+        let lambda_functions = self.lambda_functions.clone();
+        let query = FuzzyQuery::parse(&self.filter_input);
 
-        if self.filter_input.is_empty() {
+        self.matches.clear();
+        if query.is_empty() {
             self.filtered_functions = lambda_functions;
         } else {
-            let filter_lower = self.filter_input.to_lowercase();
-            let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
-
             self.filtered_functions = lambda_functions
-                .iter()
-                .filter(|name| {
-                    let function_name = name.to_lowercase();
-                    keywords
-                        .iter()
-                        .all(|&keyword| function_name.contains(keyword))
+                .into_iter()
+                .filter(|function| match query.matches(&function.name) {
+                    Some(found) => {
+                        self.matches.insert(function.name.clone(), found);
+                        true
+                    }
+                    None => false,
                 })
-                .cloned()
                 .collect();
         }
+    }
+
+    /// Re-sorts and re-groups the filtered functions, keeping the selection
+    /// on the same function or group.
+    pub fn refresh_rows(&mut self) {
+        let selected = self.selected_key();
+        self.arrange();
+        self.reselect(selected);
+    }
+
+    fn selected_key(&self) -> Option<(&'static str, String)> {
+        self.rows
+            .get(self.selected_index)
+            .map(|row| self.row_key(row))
+    }
+
+    /// Selects the row with the key, after the rows were rebuilt.
+    fn reselect(&mut self, selected: Option<(&'static str, String)>) {
+        let index = selected
+            .and_then(|(kind, name)| {
+                self.rows
+                    .iter()
+                    .position(|row| self.row_key(row) == (kind, name.clone()))
+                    // e.g. a function that is no longer pinned
+                    .or_else(|| self.rows.iter().position(|row| self.row_key(row).1 == name))
+            })
+            .unwrap_or(self.selected_index.min(self.rows.len().saturating_sub(1)));
+        self.select(index);
+    }
+
+    fn arrange(&mut self) {
+        let sort_by = self.sort_by;
+        let matches = &self.matches;
+        let last_logged = &self.last_logged;
+        let logged = |function: &FunctionInfo| {
+            last_logged
+                .as_ref()
+                .and_then(|times| times.get(&function.name).copied())
+        };
+        let score = |function: &FunctionInfo| matches.get(&function.name).map_or(0, |m| m.score);
+        // Best filter matches first, then by the sort order; unknown values sort last
+        self.filtered_functions.sort_by(|a, b| {
+            let order = score(b).cmp(&score(a)).then_with(|| match sort_by {
+                SortBy::Name => Ordering::Equal,
+                SortBy::LastModified => b.last_modified.cmp(&a.last_modified),
+                SortBy::Runtime => {
+                    (a.runtime.is_none(), &a.runtime).cmp(&(b.runtime.is_none(), &b.runtime))
+                }
+                SortBy::Memory => b.memory_mb.cmp(&a.memory_mb),
+                SortBy::LastLogged => logged(b).cmp(&logged(a)),
+            });
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+
+        if self.group_by == GroupBy::None {
+            self.rows = (0..self.filtered_functions.len())
+                .map(FunctionRow::Function)
+                .collect();
+        } else {
+            self.group_rows();
+        }
+        self.pin_sections();
+    }
+
+    fn group_rows(&mut self) {
+        // Stable, so functions keep the sort order within their group
+        let mut keyed: Vec<(Option<String>, FunctionInfo)> =
+            std::mem::take(&mut self.filtered_functions)
+                .into_iter()
+                .map(|function| (self.group_of(&function), function))
+                .collect();
+        keyed.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+
+        self.rows.clear();
+        let mut header = None;
+        for (index, (group, function)) in keyed.into_iter().enumerate() {
+            let name = group.unwrap_or_else(|| self.group_by.ungrouped_name().to_string());
+            let collapsed = self.collapsed.contains(&name);
+            match header.and_then(|header| self.rows.get_mut(header)) {
+                Some(FunctionRow::Group {
+                    name: current,
+                    count,
+                    ..
+                }) if *current == name => *count += 1,
+                _ => {
+                    header = Some(self.rows.len());
+                    self.rows.push(FunctionRow::Group {
+                        name,
+                        count: 1,
+                        collapsed,
+                    });
+                }
+            }
+            if !collapsed {
+                self.rows.push(FunctionRow::Function(index));
+            }
+            self.filtered_functions.push(function);
+        }
+    }
+
+    /// Lists favorites and recently used functions above the others.
+    fn pin_sections(&mut self) {
+        if !self.filter_input.trim().is_empty() {
+            return;
+        }
+
+        let indices: HashMap<&str, usize> = self
+            .filtered_functions
+            .iter()
+            .enumerate()
+            .map(|(index, function)| (function.name.as_str(), index))
+            .collect();
+        let pinned = |names: &[String]| -> Vec<usize> {
+            names
+                .iter()
+                .filter_map(|name| indices.get(name.as_str()).copied())
+                .collect()
+        };
+        let sections = [
+            (FAVORITES, pinned(&self.history.favorites)),
+            (RECENTLY_USED, pinned(&self.history.recent)),
+        ];
+        if sections.iter().all(|(_, functions)| functions.is_empty()) {
+            return;
+        }
+
+        let mut rows = Vec::new();
+        for (name, functions) in sections {
+            if functions.is_empty() {
+                continue;
+            }
+            let collapsed = self.collapsed.contains(name);
+            rows.push(FunctionRow::Group {
+                name: name.to_string(),
+                count: functions.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(functions.into_iter().map(FunctionRow::Pinned));
+            }
+        }
+        // Ungrouped functions get a header of their own below the pinned ones
+        if self.group_by == GroupBy::None {
+            let collapsed = self.collapsed.contains(ALL_FUNCTIONS);
+            rows.push(FunctionRow::Group {
+                name: ALL_FUNCTIONS.to_string(),
+                count: self.filtered_functions.len(),
+                collapsed,
+            });
+            if collapsed {
+                self.rows.clear();
+            }
+        }
+        rows.append(&mut self.rows);
+        self.rows = rows;
+    }
+
+    fn group_of(&self, function: &FunctionInfo) -> Option<String> {
+        match &self.group_by {
+            GroupBy::None => None,
+            GroupBy::Prefix(segments) => function
+                .name
+                .match_indices(['-', '_'])
+                .nth(segments - 1)
+                .map(|(end, _)| function.name[..end].to_string()),
+            GroupBy::Tag(key) => self
+                .tags
+                .as_ref()?
+                .get(&function.name)?
+                .get(key.as_ref()?)
+                .cloned(),
+        }
+    }
+
+    /// Identifies a row across re-arrangements: its kind and name.
+    fn row_key(&self, row: &FunctionRow) -> (&'static str, String) {
+        match row {
+            FunctionRow::Group { name, .. } => ("group", name.clone()),
+            FunctionRow::Function(index) => {
+                ("function", self.filtered_functions[*index].name.clone())
+            }
+            FunctionRow::Pinned(index) => ("pinned", self.filtered_functions[*index].name.clone()),
+        }
+    }
+
+    fn tag_keys(&self) -> BTreeSet<String> {
+        self.tags
+            .iter()
+            .flat_map(|tags| tags.values())
+            .flat_map(|tags| tags.keys().cloned())
+            .collect()
+    }
+
+    pub fn cycle_sort(&mut self, tx: &UnboundedSender<AppMessage>) {
+        self.sort_by = self.sort_by.next();
+        self.load_details(tx);
+        self.refresh_rows();
+    }
 
-        self.selected_index = 0;
-        self.list_state.select(Some(0));
+    /// No grouping, then one and two name segments, then each tag key.
+    pub fn cycle_group(&mut self, tx: &UnboundedSender<AppMessage>) {
+        self.group_by = match &self.group_by {
+            GroupBy::None => GroupBy::Prefix(1),
+            GroupBy::Prefix(1) => GroupBy::Prefix(2),
+            GroupBy::Prefix(_) => GroupBy::Tag(self.tag_keys().into_iter().next()),
+            GroupBy::Tag(Some(key)) => match self.tag_keys().into_iter().find(|next| next > key) {
+                Some(next) => GroupBy::Tag(Some(next)),
+                None => GroupBy::None,
+            },
+            GroupBy::Tag(None) => GroupBy::None,
+        };
+        self.collapsed.clear();
+        self.load_details(tx);
+        self.refresh_rows();
+    }
+
+    /// Loads what the current sort and grouping need but isn't known yet:
+    /// tags, or when each function last logged.
+    pub fn load_details(&mut self, tx: &UnboundedSender<AppMessage>) {
+        let functions = self.lambda_functions.clone();
+
+        if self.sort_by == SortBy::LastLogged
+            && self.last_logged.is_none()
+            && self.loading_last_logged.is_none()
+        {
+            let logs = Arc::clone(&self.logs);
+            let functions = functions.clone();
+            let tx = tx.clone();
+            let handle = spawn(async move {
+                let result = find_last_logged(logs, functions).await;
+                let _ = tx.send(AppMessage::FunctionsLastLogged(result));
+            });
+            self.loading_last_logged = Some(LoadingState::new(
+                "Finding when functions last logged",
+                handle,
+            ));
+        }
+
+        if matches!(self.group_by, GroupBy::Tag(_))
+            && self.tags.is_none()
+            && self.loading_tags.is_none()
+        {
+            let catalog = Arc::clone(&self.catalog);
+            let tx = tx.clone();
+            let handle = spawn(async move {
+                let result = list_tags(catalog, functions).await;
+                let _ = tx.send(AppMessage::FunctionTags(result));
+            });
+            self.loading_tags = Some(LoadingState::new("Loading tags", handle));
+        }
+    }
+
+    /// Applies loaded log activity; a cancelled load is ignored.
+    pub fn handle_last_logged(&mut self, result: Result<HashMap<String, i64>>) -> Result<()> {
+        if self.loading_last_logged.take().is_none() {
+            return Ok(());
+        }
+        self.last_logged = Some(result?);
+        self.refresh_rows();
         Ok(())
     }
 
+    /// Applies loaded tags; a cancelled load is ignored.
+    pub fn handle_tags(
+        &mut self,
+        result: Result<HashMap<String, BTreeMap<String, String>>>,
+    ) -> Result<()> {
+        if self.loading_tags.take().is_none() {
+            return Ok(());
+        }
+        self.tags = Some(result?);
+        if self.group_by == GroupBy::Tag(None) {
+            self.group_by = GroupBy::Tag(self.tag_keys().into_iter().next());
+        }
+        self.refresh_rows();
+        Ok(())
+    }
+
+    /// The in-flight refresh or detail load, if any, for the status line.
+    pub fn loading(&self) -> Option<&LoadingState> {
+        self.loading_functions
+            .as_ref()
+            .or(self.loading_last_logged.as_ref())
+            .or(self.loading_tags.as_ref())
+    }
+
+    pub fn selected(&self) -> Option<&FunctionInfo> {
+        match self.rows.get(self.selected_index)? {
+            FunctionRow::Function(index) | FunctionRow::Pinned(index) => {
+                self.filtered_functions.get(*index)
+            }
+            FunctionRow::Group { .. } => None,
+        }
+    }
+
+    /// Clears the filter and selects the function, e.g. one picked on the
+    /// profile screen.
+    pub fn select_function(&mut self, function_name: &str) -> bool {
+        self.filter_input.clear();
+        self.update_filter();
+        match self
+            .rows
+            .iter()
+            .position(|row| matches!(self.row_key(row), (kind, name) if kind != "group" && name == function_name))
+        {
+            Some(index) => {
+                self.select(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn toggle_favorite(&mut self) -> Result<()> {
+        let Some(name) = self.selected().map(|function| function.name.clone()) else {
+            return Ok(());
+        };
+        self.history.toggle_favorite(&name);
+        self.refresh_rows();
+        self.save_history()
+    }
+
+    /// Remembers that the function's logs were opened.
+    pub fn record_use(&mut self, function_name: &str) -> Result<()> {
+        self.history.record_use(function_name);
+        self.refresh_rows();
+        self.save_history()
+    }
+
+    pub fn save_history(&self) -> Result<()> {
+        save_function_history(&self.profile.name, &self.profile.region, &self.history)
+    }
+
+    /// The header of the group the selected row belongs to.
+    fn selected_group(&self) -> Option<(usize, String)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .take(self.selected_index + 1)
+            .rev()
+            .find_map(|(index, row)| match row {
+                FunctionRow::Group { name, .. } => Some((index, name.clone())),
+                FunctionRow::Function(_) | FunctionRow::Pinned(_) => None,
+            })
+    }
+
+    /// Folds or unfolds the group whose header is selected.
+    pub fn toggle_group(&mut self) {
+        if let Some(FunctionRow::Group {
+            name, collapsed, ..
+        }) = self.rows.get(self.selected_index)
+        {
+            let name = name.clone();
+            if *collapsed {
+                self.collapsed.remove(&name);
+            } else {
+                self.collapsed.insert(name);
+            }
+            self.refresh_rows();
+        }
+    }
+
+    /// Folds the group of the selected row and selects its header.
+    pub fn collapse_group(&mut self) {
+        if let Some((index, name)) = self.selected_group() {
+            self.collapsed.insert(name);
+            self.select(index);
+            self.refresh_rows();
+        }
+    }
+
+    pub fn expand_group(&mut self) {
+        if let Some((_, name)) = self.selected_group() {
+            if self.collapsed.remove(&name) {
+                self.refresh_rows();
+            }
+        }
+    }
+
+    pub fn toggle_columns(&mut self) {
+        self.show_columns = !self.show_columns;
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected_index = index;
+        self.table_state.select(Some(index));
+    }
+
     pub fn next(&mut self) {
-        if !self.filtered_functions.is_empty() {
-            self.selected_index = (self.selected_index + 1).min(self.filtered_functions.len() - 1);
-            self.list_state.select(Some(self.selected_index));
+        if !self.rows.is_empty() {
+            self.select((self.selected_index + 1).min(self.rows.len() - 1));
         }
     }
 
     pub fn previous(&mut self) {
-        if !self.filtered_functions.is_empty() {
-            self.selected_index = self.selected_index.saturating_sub(1);
-            self.list_state.select(Some(self.selected_index));
+        if !self.rows.is_empty() {
+            self.select(self.selected_index.saturating_sub(1));
         }
     }
 }
 
-async fn update_functions_in_background(
-    profile_name: String,
-    profile_region: String,
-    lambda_functions: Arc<Mutex<Vec<String>>>,
-) -> Result<()> {
-    let config = aws_config::defaults(BehaviorVersion::latest())
-        .profile_name(&profile_name)
-        .region(Region::new(profile_region.clone()))
-        .load()
-        .await;
-
-    let client = LambdaClient::new(&config);
-    let mut functions = Vec::new();
-    let mut next_marker = None;
+async fn list_functions(catalog: Arc<dyn FunctionCatalog>) -> Result<Vec<FunctionInfo>> {
+    let mut functions = catalog.list_functions().await?;
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(functions)
+}
 
-    loop {
-        let mut request = client.list_functions();
-        if let Some(marker) = next_marker {
-            request = request.marker(marker);
+/// The latest event time of each function's log group, from its most
+/// recently written stream.
+async fn find_last_logged(
+    logs: Arc<dyn LogBackend>,
+    functions: Vec<FunctionInfo>,
+) -> Result<HashMap<String, i64>> {
+    fetch_each(functions, move |function| {
+        let logs = Arc::clone(&logs);
+        async move {
+            match logs
+                .describe_log_streams(&lambda_log_group(&function.name), 1)
+                .await
+            {
+                Ok(streams) => Ok(streams.first().and_then(|stream| stream.last_event_time)),
+                // The function never logged
+                Err(e) if AwsError::from_anyhow(&e).kind == AwsErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            }
         }
+    })
+    .await
+}
 
-        let response = request.send().await?;
-        let function_list = response.functions();
-        for function in function_list {
-            if let Some(name) = &function.function_name {
-                functions.push(name.clone())
-            }
+async fn list_tags(
+    catalog: Arc<dyn FunctionCatalog>,
+    functions: Vec<FunctionInfo>,
+) -> Result<HashMap<String, BTreeMap<String, String>>> {
+    fetch_each(functions, move |function| {
+        let catalog = Arc::clone(&catalog);
+        async move {
+            let tags = catalog.list_tags(&function).await?;
+            Ok(Some(tags).filter(|tags| !tags.is_empty()))
+        }
+    })
+    .await
+}
+
+/// Runs `fetch` for every function, a few at a time, keeping the results
+/// by function name.
+async fn fetch_each<T, F, Fut>(functions: Vec<FunctionInfo>, fetch: F) -> Result<HashMap<String, T>>
+where
+    T: Send + 'static,
+    F: Fn(FunctionInfo) -> Fut,
+    Fut: Future<Output = Result<Option<T>>> + Send + 'static,
+{
+    let mut results = HashMap::new();
+    let mut tasks = JoinSet::new();
+    let mut functions = functions.into_iter();
+
+    loop {
+        while tasks.len() < CONCURRENT_REQUESTS {
+            let Some(function) = functions.next() else {
+                break;
+            };
+            let name = function.name.clone();
+            let fetched = fetch(function);
+            tasks.spawn(async move { (name, fetched.await) });
         }
 
-        next_marker = response.next_marker().map(String::from);
-        if next_marker.is_none() {
+        let Some(joined) = tasks.join_next().await else {
             break;
+        };
+        let (name, value) = joined?;
+        if let Some(value) = value? {
+            results.insert(name, value);
         }
     }
 
-    functions.sort();
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+
+    fn selection(names: &[&str]) -> FunctionSelection {
+        let fake = Arc::new(FakeBackend::new());
+        let backend = Backend {
+            logs: fake.clone(),
+            functions: fake,
+        };
+        let profile = Profile {
+            name: "test".to_string(),
+            region: "eu-west-1".to_string(),
+        };
+        let mut selection = FunctionSelection::new(profile, &backend, Duration::minutes(10));
+        selection.replace_functions(functions(names), Utc::now().timestamp_millis());
+        selection
+    }
+
+    fn functions(names: &[&str]) -> Vec<FunctionInfo> {
+        names.iter().map(|name| FunctionInfo::named(name)).collect()
+    }
+
+    fn select_row(selection: &mut FunctionSelection, kind: &'static str, name: &str) {
+        let index = selection
+            .rows
+            .iter()
+            .position(|row| selection.row_key(row) == (kind, name.to_string()))
+            .unwrap();
+        selection.select(index);
+    }
+
+    #[test]
+    fn goes_stale_after_the_ttl() {
+        let mut selection = selection(&["orders"]);
+        assert!(!selection.is_stale());
+
+        let now = Utc::now().timestamp_millis();
+        selection.fetched_at = Some(now - Duration::minutes(9).num_milliseconds());
+        assert!(!selection.is_stale());
+        selection.fetched_at = Some(now - Duration::minutes(11).num_milliseconds());
+        assert!(selection.is_stale());
+        selection.fetched_at = None;
+        assert!(selection.is_stale());
+    }
+
+    #[test]
+    fn keeps_the_selection_when_a_refresh_lands() {
+        let mut selection = selection(&["billing", "orders", "users"]);
+        select_row(&mut selection, "function", "orders");
+
+        // A new function is listed above it
+        selection.replace_functions(functions(&["auth", "billing", "orders", "users"]), 0);
+        assert_eq!(selection.selected().unwrap().name, "orders");
+        assert_eq!(selection.fetched_at, Some(0));
 
-    // Update cache
-    cache_functions(&profile_name, &profile_region, &functions)?;
+        // The same function is also pinned, but the selected row isn't
+        selection.history.toggle_favorite("orders");
+        selection.refresh_rows();
+        select_row(&mut selection, "function", "orders");
+        selection.replace_functions(functions(&["auth", "orders", "users"]), 0);
+        assert_eq!(
+            selection.row_key(&selection.rows[selection.selected_index]),
+            ("function", "orders".to_string())
+        );
 
-    // Update the shared functions list
-    let mut functions_lock = lambda_functions.lock().unwrap();
-    functions_lock.clear();
-    functions_lock.extend(functions);
+        // A removed function leaves the selection where it was
+        select_row(&mut selection, "function", "users");
+        let index = selection.selected_index;
+        selection.replace_functions(functions(&["auth", "orders"]), 0);
+        assert_eq!(
+            selection.selected_index,
+            index.min(selection.rows.len() - 1)
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn groups_by_name_prefix() {
+        let mut selection = selection(&[]);
+        let cases = [
+            (1, "orders-prod-processor", Some("orders")),
+            (2, "orders-prod-processor", Some("orders-prod")),
+            (2, "orders_prod-processor", Some("orders_prod")),
+            (3, "orders-prod-processor", None),
+            (2, "orders-prod", None),
+            (1, "standalone", None),
+        ];
+        for (segments, name, group) in cases {
+            selection.group_by = GroupBy::Prefix(segments);
+            assert_eq!(
+                selection.group_of(&FunctionInfo::named(name)).as_deref(),
+                group,
+                "{} {}",
+                segments,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn groups_by_tag() {
+        let mut selection = selection(&["billing", "orders", "scratch", "users"]);
+        let tags = [
+            ("orders", "team", "shop"),
+            ("users", "team", "identity"),
+            ("billing", "team", "shop"),
+            ("billing", "stage", "prod"),
+            ("scratch", "stage", "dev"),
+        ];
+        let mut by_function: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+        for (function, key, value) in tags {
+            by_function
+                .entry(function.to_string())
+                .or_default()
+                .insert(key.to_string(), value.to_string());
+        }
+        selection.tags = Some(by_function);
+        selection.group_by = GroupBy::Tag(Some("team".to_string()));
+        selection.refresh_rows();
+
+        let group = |name: &str, count| FunctionRow::Group {
+            name: name.to_string(),
+            count,
+            collapsed: false,
+        };
+        let names: Vec<&str> = selection
+            .filtered_functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+        assert_eq!(names, ["users", "billing", "orders", "scratch"]);
+        assert_eq!(
+            selection.rows,
+            [
+                group("identity", 1),
+                FunctionRow::Function(0),
+                group("shop", 2),
+                FunctionRow::Function(1),
+                FunctionRow::Function(2),
+                group("(untagged)", 1),
+                FunctionRow::Function(3),
+            ]
+        );
+
+        // Until tags are loaded, nothing has a group
+        selection.tags = None;
+        assert_eq!(selection.group_of(&FunctionInfo::named("orders")), None);
+        selection.group_by = GroupBy::Tag(None);
+        selection.tags = Some(HashMap::new());
+        assert_eq!(selection.group_of(&FunctionInfo::named("orders")), None);
+    }
 }
@@ -0,0 +1,335 @@
+use chrono::{DateTime, Local};
+use std::cmp::Ordering;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app_state::{loading::LoadingState, AppMessage};
+use crate::backend::{InsightsQuery, LogBackend, LogSource, QueryResults};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_QUERY: &str = "fields @timestamp, @message | sort @timestamp desc | limit 100";
+
+static NEXT_QUERY_VIEW_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Runs Logs Insights queries over the log group for the range
+/// picked in the date selection, and shows the results as a table.
+#[derive(Debug)]
+pub struct InsightsView {
+    pub id: u64,
+    pub source: LogSource,
+    pub from_date: DateTime<Local>,
+    pub to_date: DateTime<Local>,
+    pub query_input: String,
+    pub editing: bool,
+    pub results: Option<QueryResults>,
+    pub columns: Vec<String>,
+    pub selected_row: usize,
+    pub sort_column: Option<usize>,
+    pub sort_descending: bool,
+    pub loading: Option<LoadingState>,
+    /// The running query, stopped when the view stops polling it.
+    pub query_id: Option<String>,
+    backend: Arc<dyn LogBackend>,
+}
+
+impl InsightsView {
+    pub fn new(
+        backend: Arc<dyn LogBackend>,
+        source: LogSource,
+        from_date: DateTime<Local>,
+        to_date: DateTime<Local>,
+    ) -> Self {
+        Self {
+            id: NEXT_QUERY_VIEW_ID.fetch_add(1, AtomicOrdering::Relaxed),
+            source,
+            from_date,
+            to_date,
+            query_input: DEFAULT_QUERY.to_string(),
+            editing: true,
+            results: None,
+            columns: Vec::new(),
+            selected_row: 0,
+            sort_column: None,
+            sort_descending: false,
+            loading: None,
+            query_id: None,
+            backend,
+        }
+    }
+
+    /// Starts the query and polls `GetQueryResults` in the background until
+    /// it finishes; every poll is reported as `AppMessage::QueryResults`.
+    pub fn run_query(&mut self, tx: &UnboundedSender<AppMessage>) {
+        self.cancel_query();
+        let backend = Arc::clone(&self.backend);
+        let query = InsightsQuery {
+            log_group_name: self.source.log_group_name.clone(),
+            start_time: self.from_date.timestamp_millis(),
+            end_time: self.to_date.timestamp_millis(),
+            query_string: self.query_input.clone(),
+        };
+        // Results of an earlier run still in flight become stale
+        self.id = NEXT_QUERY_VIEW_ID.fetch_add(1, AtomicOrdering::Relaxed);
+        let view_id = self.id;
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let query_id = match backend.start_query(&query).await {
+                Ok(query_id) => {
+                    let message = AppMessage::QueryStarted {
+                        view_id,
+                        query_id: query_id.clone(),
+                    };
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                    query_id
+                }
+                Err(error) => {
+                    let _ = tx.send(AppMessage::QueryFailed { view_id, error });
+                    return;
+                }
+            };
+
+            loop {
+                let result = backend.get_query_results(&query_id).await;
+                let finished = result.as_ref().map_or(true, |r| r.status.is_finished());
+                let message = match result {
+                    Ok(results) => AppMessage::QueryResults { view_id, results },
+                    Err(error) => AppMessage::QueryFailed { view_id, error },
+                };
+                if tx.send(message).is_err() || finished {
+                    break;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        });
+
+        self.editing = false;
+        self.loading = Some(LoadingState::new("Running query", handle));
+    }
+
+    pub fn handle_results(&mut self, results: QueryResults) {
+        if results.status.is_finished() {
+            self.loading = None;
+            self.query_id = None;
+        }
+
+        // Columns in order of first appearance; @ptr is only for GetLogRecord
+        self.columns.clear();
+        for (field, _) in results.rows.iter().flatten() {
+            if field != "@ptr" && !self.columns.contains(field) {
+                self.columns.push(field.clone());
+            }
+        }
+        if self
+            .sort_column
+            .is_some_and(|column| column >= self.columns.len())
+        {
+            self.sort_column = None;
+        }
+
+        self.results = Some(results);
+        self.sort_rows();
+        self.selected_row = self.selected_row.min(self.row_count().saturating_sub(1));
+    }
+
+    pub fn handle_error(&mut self) {
+        self.loading = None;
+        self.query_id = None;
+        self.editing = true;
+    }
+
+    /// Cancels a running query. Returns `false` if nothing was running.
+    pub fn cancel_query(&mut self) -> bool {
+        if let Some(query_id) = self.query_id.take() {
+            let backend = Arc::clone(&self.backend);
+            spawn(async move {
+                // It may have finished in the meantime
+                let _ = backend.stop_query(&query_id).await;
+            });
+        }
+        self.loading.take().is_some()
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.results.as_ref().map_or(0, |r| r.rows.len())
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> &str {
+        let Some(fields) = self.results.as_ref().and_then(|r| r.rows.get(row)) else {
+            return "";
+        };
+        let name = &self.columns[column];
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map_or("", |(_, value)| value.as_str())
+    }
+
+    pub fn next_row(&mut self) {
+        if self.selected_row + 1 < self.row_count() {
+            self.selected_row += 1;
+        }
+    }
+
+    pub fn previous_row(&mut self) {
+        self.selected_row = self.selected_row.saturating_sub(1);
+    }
+
+    pub fn page_down(&mut self, rows: usize) {
+        self.selected_row = (self.selected_row + rows).min(self.row_count().saturating_sub(1));
+    }
+
+    pub fn page_up(&mut self, rows: usize) {
+        self.selected_row = self.selected_row.saturating_sub(rows);
+    }
+
+    /// Sorts by the next column to the right, starting from the first.
+    pub fn next_sort_column(&mut self) {
+        if self.columns.is_empty() {
+            return;
+        }
+        self.sort_column = Some(self.sort_column.map_or(0, |c| (c + 1) % self.columns.len()));
+        self.sort_rows();
+    }
+
+    pub fn previous_sort_column(&mut self) {
+        if self.columns.is_empty() {
+            return;
+        }
+        let count = self.columns.len();
+        self.sort_column = Some(
+            self.sort_column
+                .map_or(count - 1, |c| (c + count - 1) % count),
+        );
+        self.sort_rows();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        let (Some(column), Some(results)) = (self.sort_column, self.results.as_mut()) else {
+            return;
+        };
+        let name = &self.columns[column];
+        let value = |row: &Vec<(String, String)>| {
+            row.iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default()
+        };
+
+        results.rows.sort_by(|a, b| {
+            let ordering = compare_cells(&value(a), &value(b));
+            if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+/// Numeric cells such as counts sort by value and before any text, so
+/// columns mixing numbers with text or `-` still have a total order.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+    use crate::backend::{LogEvent, QueryStatistics, QueryStatus};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    const GROUP: &str = "/aws/lambda/orders-prod-processor";
+
+    fn view(backend: Arc<FakeBackend>) -> InsightsView {
+        let now = Local::now();
+        InsightsView::new(
+            backend,
+            LogSource::log_group(GROUP),
+            now - chrono::Duration::hours(1),
+            now,
+        )
+    }
+
+    #[test]
+    fn sorts_mixed_columns() {
+        let mut view = view(Arc::new(FakeBackend::new()));
+        let cells = ["1a", "9", "-", "10", "NaN", "2.5", "", "-3", "1a"];
+        view.handle_results(QueryResults {
+            status: QueryStatus::Complete,
+            rows: cells
+                .iter()
+                .map(|cell| vec![("count".to_string(), cell.to_string())])
+                .collect(),
+            statistics: QueryStatistics::default(),
+        });
+        let column = |view: &InsightsView| -> Vec<String> {
+            (0..view.row_count())
+                .map(|row| view.cell(row, 0).to_string())
+                .collect()
+        };
+
+        view.next_sort_column();
+        assert_eq!(
+            column(&view),
+            ["-3", "2.5", "9", "10", "NaN", "", "-", "1a", "1a"]
+        );
+        view.toggle_sort_order();
+        assert_eq!(
+            column(&view),
+            ["1a", "1a", "-", "", "NaN", "10", "9", "2.5", "-3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_the_query_when_cancelled() {
+        let timestamp = Local::now().timestamp_millis() - 60_000;
+        let backend = Arc::new(FakeBackend::new().with_events(
+            GROUP,
+            vec![LogEvent {
+                event_id: None,
+                log_stream_name: Some("stream".to_string()),
+                timestamp,
+                ingestion_time: timestamp,
+                message: "hello".to_string(),
+            }],
+        ));
+        let mut view = view(Arc::clone(&backend));
+        let (tx, mut rx) = unbounded_channel();
+        view.run_query(&tx);
+
+        let Some(AppMessage::QueryStarted { view_id, query_id }) = rx.recv().await else {
+            panic!("the query didn't start");
+        };
+        assert_eq!(view_id, view.id);
+        view.query_id = Some(query_id.clone());
+
+        assert!(view.cancel_query());
+        assert!(view.query_id.is_none());
+        assert!(!view.cancel_query());
+        for _ in 0..100 {
+            let results = backend.get_query_results(&query_id).await.unwrap();
+            if results.status == QueryStatus::Cancelled {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("the query was not stopped");
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use crate::backend::LogEvent;
+use crate::invocations::Invocation;
+
+/// A line of the invocation view: an invocation, or one of its events when
+/// it is expanded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvocationRow {
+    Invocation(usize),
+    Event(usize, usize),
+}
+
+/// The log viewer's events collapsed into one expandable row per Lambda
+/// invocation.
+#[derive(Debug, Default)]
+pub struct InvocationList {
+    pub invocations: Vec<Invocation>,
+    pub rows: Vec<InvocationRow>,
+    pub selected: usize,
+    expanded: HashSet<String>,
+}
+
+impl InvocationList {
+    /// Replaces the invocations with those that `matches`. Expanded rows and
+    /// the selection are kept.
+    pub fn rebuild(&mut self, invocations: &[Invocation], matches: impl Fn(&Invocation) -> bool) {
+        let selected_row = self.rows.get(self.selected).copied();
+        // Events without a request ID are told apart by the event itself
+        let selected_key = self.selected_invocation().map(|i| {
+            (
+                i.request_id.clone(),
+                i.events.first().map(LogEvent::dedup_key),
+            )
+        });
+
+        self.invocations = invocations
+            .iter()
+            .filter(|invocation| matches(invocation))
+            .cloned()
+            .collect();
+        self.update_rows();
+
+        self.selected = selected_key
+            .and_then(|(request_id, first_event)| {
+                let invocation = self.invocations.iter().position(|i| {
+                    i.request_id == request_id
+                        && (request_id.is_some()
+                            || i.events.first().map(LogEvent::dedup_key) == first_event)
+                })?;
+                let row = match selected_row {
+                    Some(InvocationRow::Event(_, event)) => InvocationRow::Event(invocation, event),
+                    _ => InvocationRow::Invocation(invocation),
+                };
+                self.rows.iter().position(|r| *r == row)
+            })
+            .unwrap_or(0);
+    }
+
+    fn update_rows(&mut self) {
+        self.rows = self
+            .invocations
+            .iter()
+            .enumerate()
+            .flat_map(|(i, invocation)| {
+                let events = if self.is_expanded(invocation) {
+                    invocation.events.len()
+                } else {
+                    0
+                };
+                std::iter::once(InvocationRow::Invocation(i))
+                    .chain((0..events).map(move |event| InvocationRow::Event(i, event)))
+            })
+            .collect();
+    }
+
+    pub fn is_expanded(&self, invocation: &Invocation) -> bool {
+        invocation
+            .request_id
+            .as_ref()
+            .is_some_and(|request_id| self.expanded.contains(request_id))
+    }
+
+    pub fn selected_invocation(&self) -> Option<&Invocation> {
+        match self.rows.get(self.selected)? {
+            InvocationRow::Invocation(i) | InvocationRow::Event(i, _) => self.invocations.get(*i),
+        }
+    }
+
+    /// 1-based number of the selected invocation, 0 when there are none.
+    pub fn selected_position(&self) -> usize {
+        match self.rows.get(self.selected) {
+            Some(InvocationRow::Invocation(i) | InvocationRow::Event(i, _)) => i + 1,
+            None => 0,
+        }
+    }
+
+    /// The selected event, or the first event of the selected invocation.
+    pub fn selected_event(&self) -> Option<&LogEvent> {
+        match self.rows.get(self.selected)? {
+            InvocationRow::Invocation(i) => self.invocations.get(*i)?.events.first(),
+            InvocationRow::Event(i, event) => self.invocations.get(*i)?.events.get(*event),
+        }
+    }
+
+    pub fn is_event_selected(&self) -> bool {
+        matches!(self.rows.get(self.selected), Some(InvocationRow::Event(..)))
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(request_id) = self
+            .selected_invocation()
+            .and_then(|i| i.request_id.clone())
+        {
+            self.expanded.insert(request_id);
+            self.update_rows();
+        }
+    }
+
+    /// Collapses the selected invocation, moving the selection up to it.
+    pub fn collapse(&mut self) {
+        let Some(InvocationRow::Invocation(i) | InvocationRow::Event(i, _)) =
+            self.rows.get(self.selected).copied()
+        else {
+            return;
+        };
+        if let Some(request_id) = &self.invocations[i].request_id {
+            self.expanded.remove(request_id);
+        }
+        self.update_rows();
+        self.selected = self
+            .rows
+            .iter()
+            .position(|row| *row == InvocationRow::Invocation(i))
+            .unwrap_or(0);
+    }
+
+    pub fn toggle_expand(&mut self) {
+        match self.selected_invocation() {
+            Some(invocation) if self.is_expanded(invocation) => self.collapse(),
+            Some(_) => self.expand(),
+            None => {}
+        }
+    }
+
+    /// Selects the first invocation starting at or after `timestamp`, or the
+    /// last one.
+    pub fn select_time(&mut self, timestamp: i64) {
+        let invocation = self
+            .invocations
+            .iter()
+            .position(|i| i.start_time >= timestamp)
+            .unwrap_or(self.invocations.len().saturating_sub(1));
+        self.selected = self
+            .rows
+            .iter()
+            .position(|row| *row == InvocationRow::Invocation(invocation))
+            .unwrap_or(0);
+    }
+
+    pub fn move_selection(&mut self, offset: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(offset).min(last);
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Instant;
+use tokio::task::{Id, JoinHandle};
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// An in-flight background task and its progress. Dropping it cancels the task.
+#[derive(Debug)]
+pub struct LoadingState {
+    pub label: String,
+    pub pages: usize,
+    pub events: usize,
+    started: Instant,
+    handle: JoinHandle<()>,
+}
+
+impl LoadingState {
+    pub fn new(label: &str, handle: JoinHandle<()>) -> Self {
+        Self {
+            label: label.to_string(),
+            pages: 0,
+            events: 0,
+            started: Instant::now(),
+            handle,
+        }
+    }
+
+    pub fn is_task(&self, id: Id) -> bool {
+        self.handle.id() == id
+    }
+
+    pub fn spinner(&self) -> &'static str {
+        let frame = self.started.elapsed().as_millis() / 80;
+        SPINNER_FRAMES[frame as usize % SPINNER_FRAMES.len()]
+    }
+
+    pub fn record_page(&mut self, events: usize) {
+        self.pages += 1;
+        self.events += events;
+    }
+
+    /// e.g. `⠙ Loading logs… 3 pages, 300 events`
+    pub fn summary(&self) -> String {
+        if self.pages == 0 {
+            format!("{} {}…", self.spinner(), self.label)
+        } else {
+            format!(
+                "{} {}… {} pages, {} events",
+                self.spinner(),
+                self.label,
+                self.pages,
+                self.events
+            )
+        }
+    }
+}
+
+impl Drop for LoadingState {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app_state::{loading::LoadingState, AppMessage};
+use crate::backend::{LogBackend, LogGroupInfo};
+use crate::toml_parser::Profile;
+use crate::utils::file_utils::{cache_log_groups, load_cached_log_groups};
+
+/// Browses every CloudWatch log group of the profile, not only Lambda ones.
+#[derive(Debug)]
+pub struct LogGroupSelection {
+    pub profile: Profile,
+    pub log_groups: Vec<LogGroupInfo>,
+    pub filtered_log_groups: Vec<LogGroupInfo>,
+    pub selected_index: usize,
+    pub filter_input: String,
+    /// Applied `DescribeLogGroups` name prefix; changing it lists again.
+    pub prefix: String,
+    /// The prefix being edited, until it is applied with Enter.
+    pub prefix_input: Option<String>,
+    pub loading: Option<LoadingState>,
+    backend: Arc<dyn LogBackend>,
+}
+
+impl LogGroupSelection {
+    pub fn new(profile: Profile, backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            profile,
+            log_groups: Vec::new(),
+            filtered_log_groups: Vec::new(),
+            selected_index: 0,
+            filter_input: String::new(),
+            prefix: String::new(),
+            prefix_input: None,
+            loading: None,
+            backend,
+        }
+    }
+
+    /// Shows the cached list, if any, while the fresh one is being fetched.
+    pub fn load_cached(&mut self) -> Result<()> {
+        if let Some(log_groups) = load_cached_log_groups(&self.profile.name, &self.profile.region)?
+        {
+            self.log_groups = log_groups;
+            self.update_filter();
+        }
+        Ok(())
+    }
+
+    /// Lists the log groups matching the current prefix in the background;
+    /// the result arrives as `AppMessage::LogGroupsLoaded`.
+    pub fn start_loading(&mut self, tx: &UnboundedSender<AppMessage>) {
+        let backend = Arc::clone(&self.backend);
+        let prefix = self.prefix.clone();
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let result = backend
+                .describe_log_groups(Some(prefix.as_str()).filter(|p| !p.is_empty()))
+                .await;
+            let _ = tx.send(AppMessage::LogGroupsLoaded { prefix, result });
+        });
+
+        self.loading = Some(LoadingState::new("Loading log groups", handle));
+    }
+
+    /// Applies a listing; results for an older prefix or a cancelled load
+    /// are ignored. Only the full, unprefixed list is cached.
+    pub fn handle_loaded(
+        &mut self,
+        prefix: String,
+        result: Result<Vec<LogGroupInfo>>,
+    ) -> Result<()> {
+        if prefix != self.prefix || self.loading.take().is_none() {
+            return Ok(());
+        }
+
+        let log_groups = result?;
+        if prefix.is_empty() {
+            cache_log_groups(&self.profile.name, &self.profile.region, &log_groups)?;
+        }
+        self.log_groups = log_groups;
+        self.update_filter();
+        Ok(())
+    }
+
+    pub fn edit_prefix(&mut self) {
+        self.prefix_input = Some(self.prefix.clone());
+    }
+
+    pub fn cancel_prefix(&mut self) {
+        self.prefix_input = None;
+    }
+
+    pub fn apply_prefix(&mut self, tx: &UnboundedSender<AppMessage>) {
+        let Some(input) = self.prefix_input.take() else {
+            return;
+        };
+        self.prefix = input.trim().to_string();
+        // Narrow what we already have until the listing arrives
+        self.update_filter();
+        self.start_loading(tx);
+    }
+
+    pub fn update_filter(&mut self) {
+        let selected_name = self.selected().map(|group| group.name.clone());
+        let filter_lower = self.filter_input.to_lowercase();
+        let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
+
+        self.filtered_log_groups = self
+            .log_groups
+            .iter()
+            .filter(|group| group.name.starts_with(&self.prefix))
+            .filter(|group| {
+                let name = group.name.to_lowercase();
+                keywords.iter().all(|&keyword| name.contains(keyword))
+            })
+            .cloned()
+            .collect();
+
+        // Keep the selection on the same group when the list is refreshed
+        self.selected_index = selected_name
+            .and_then(|name| {
+                self.filtered_log_groups
+                    .iter()
+                    .position(|group| group.name == name)
+            })
+            .unwrap_or(0);
+    }
+
+    pub fn selected(&self) -> Option<&LogGroupInfo> {
+        self.filtered_log_groups.get(self.selected_index)
+    }
+
+    pub fn next(&mut self) {
+        if !self.filtered_log_groups.is_empty() {
+            self.selected_index = (self.selected_index + 1).min(self.filtered_log_groups.len() - 1);
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+}
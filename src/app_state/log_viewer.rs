@@ -1,132 +1,1033 @@
 use anyhow::Result;
-use aws_config::Region;
-use aws_sdk_cloudwatchlogs::types::OutputLogEvent;
-use aws_sdk_cloudwatchlogs::Client as CloudWatchLogsClient;
-use chrono::{DateTime, Local};
-use serde_json;
+use chrono::{DateTime, Local, TimeZone};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::Id;
 
+use crate::app_state::{invocation_list::InvocationList, loading::LoadingState, AppMessage};
+use crate::backend::{
+    fetch_range, LogBackend, LogEvent, LogQuery, LogSource, LogStreamInfo, StreamPage,
+};
+use crate::error::AwsError;
+use crate::export::{export_to_file, ExportFormat};
+use crate::invocations::{group_invocations, InvocationStats, Problems, RequestIndex};
+use crate::recent::{find_recent_logs, RecentLimit, RecentLogs};
+use crate::timeline::{Histogram, TIMELINE_BUCKETS};
+use crate::utils::time_utils::DisplayTimezone;
 use crate::utils::ui_utils::format_json;
 
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Re-query a little before the newest event to catch late ingested events
+const FOLLOW_OVERLAP_MS: i64 = 10_000;
+// A poll reaches back at most this far; an older gap is left to the pagination
+const FOLLOW_MAX_POLL_MS: i64 = 5 * 60_000;
+// Fetch more once the selection is this many rows from the end of the list
+const PREFETCH_MARGIN: usize = 50;
+const ON_DEMAND_PAGES: usize = 2;
+// Zooming in stops at this range width
+const MIN_RANGE_MS: i64 = 60_000;
+
+static NEXT_VIEWER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Quick filter on the platform problems of an event's invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ProblemFilter {
+    #[default]
+    All,
+    Failures,
+    ColdStarts,
+}
+
+impl ProblemFilter {
+    pub fn next(self) -> Self {
+        match self {
+            ProblemFilter::All => ProblemFilter::Failures,
+            ProblemFilter::Failures => ProblemFilter::ColdStarts,
+            ProblemFilter::ColdStarts => ProblemFilter::All,
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            ProblemFilter::All => "All",
+            ProblemFilter::Failures => "Timeouts, crashes and OOM",
+            ProblemFilter::ColdStarts => "Cold starts",
+        }
+    }
+
+    pub fn matches(&self, problems: Problems) -> bool {
+        match self {
+            ProblemFilter::All => true,
+            ProblemFilter::Failures => problems.is_failure(),
+            ProblemFilter::ColdStarts => problems.cold_start,
+        }
+    }
+}
+
+/// Where a viewer reading a single stream with `GetLogEvents` is, in both
+/// directions.
+#[derive(Debug, Default)]
+struct StreamCursor {
+    name: String,
+    forward_token: Option<String>,
+    backward_token: Option<String>,
+    at_head: bool,
+    at_tail: bool,
+}
+
 #[derive(Debug)]
 pub struct LogViewer {
-    pub function_name: String,
+    pub id: u64,
+    pub source: LogSource,
     pub from_date: DateTime<Local>,
     pub to_date: DateTime<Local>,
-    pub logs: Arc<Mutex<Vec<OutputLogEvent>>>,
-    pub filtered_logs: Vec<OutputLogEvent>,
+    pub logs: Arc<Mutex<Vec<LogEvent>>>,
+    pub filtered_logs: Vec<LogEvent>,
     pub filter_input: String,
+    /// Applied CloudWatch filter pattern; changing it re-fetches the range.
+    pub server_filter: String,
+    /// The server filter being edited, until it is applied with Enter.
+    pub server_filter_input: Option<String>,
+    /// Only the lines of this request are shown, see `show_request`.
+    pub request_filter: Option<String>,
+    filter_before_request: String,
+    pub problem_filter: ProblemFilter,
+    requests: RequestIndex,
+    /// Set while events are grouped by invocation.
+    pub invocations: Option<InvocationList>,
+    /// Set while the stats panel is shown.
+    pub stats: Option<InvocationStats>,
+    /// The timeline bucket picked with the keyboard, set while the timeline
+    /// has focus.
+    pub timeline_cursor: Option<usize>,
     pub scroll_offset: usize, // Changed from scroll_position
     pub selected_log: Option<usize>,
     pub expanded: bool,
-    cloudwatch_client: Option<CloudWatchLogsClient>,
+    backend: Arc<dyn LogBackend>,
     pub scroll_position: usize,
     pub start_index: usize, // Add this field to track list scroll position
+    pub export_menu: Option<usize>,
+    pub status_message: Option<String>,
+    pub following: bool,
+    pub loading: Option<LoadingState>,
+    last_poll: Option<Instant>,
+    /// The part of the range polled by the follow poll in flight and its
+    /// task, if any.
+    poll_in_flight: Option<((i64, i64), Id)>,
+    seen_events: HashSet<String>,
+    next_token: Option<String>,
+    /// Parts of the range fetched completely, in time order.
+    covered: Vec<(i64, i64)>,
+    /// Parts of the range still to fetch, oldest first. `next_token` pages
+    /// through the first one.
+    pending: Vec<(i64, i64)>,
+    range_changed: bool,
+    auto_load: bool,
+    stream: Option<StreamCursor>,
+    /// Set until the latest events have been found, see `LogViewer::recent`.
+    recent: Option<RecentLimit>,
+    /// Timestamps are shown and exported in this timezone.
+    pub timezone: DisplayTimezone,
 }
 
 impl LogViewer {
     pub fn new(
-        function_name: String,
+        backend: Arc<dyn LogBackend>,
+        source: LogSource,
         from_date: DateTime<Local>,
         to_date: DateTime<Local>,
     ) -> Self {
         Self {
-            function_name,
+            id: NEXT_VIEWER_ID.fetch_add(1, Ordering::Relaxed),
+            source,
             from_date,
             to_date,
             logs: Arc::new(Mutex::new(Vec::new())),
             filtered_logs: Vec::new(),
             filter_input: String::new(),
+            server_filter: String::new(),
+            server_filter_input: None,
+            request_filter: None,
+            filter_before_request: String::new(),
+            problem_filter: ProblemFilter::All,
+            requests: RequestIndex::default(),
+            invocations: None,
+            stats: None,
+            timeline_cursor: None,
             scroll_offset: 0,
             selected_log: None,
             expanded: false,
-            cloudwatch_client: None,
+            backend,
             scroll_position: 0,
             start_index: 0, // Initialize start_index
+            export_menu: None,
+            status_message: None,
+            following: false,
+            loading: None,
+            last_poll: None,
+            poll_in_flight: None,
+            seen_events: HashSet::new(),
+            next_token: None,
+            covered: Vec::new(),
+            pending: vec![(from_date.timestamp_millis(), to_date.timestamp_millis())],
+            range_changed: false,
+            auto_load: true,
+            stream: None,
+            recent: None,
+            timezone: DisplayTimezone::Local,
         }
     }
 
-    pub async fn initialize(&mut self, profile_name: String, region: String) -> Result<()> {
-        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::v2024_03_28())
-            .profile_name(profile_name)
-            .region(Region::new(region.clone()))
-            .load()
-            .await;
+    /// A viewer over a single stream, opened at its newest events and paged
+    /// backwards as the selection moves up.
+    pub fn for_stream(
+        backend: Arc<dyn LogBackend>,
+        source: LogSource,
+        stream: &LogStreamInfo,
+    ) -> Self {
+        let to_local = |ms: Option<i64>| {
+            ms.and_then(|ms| Local.timestamp_millis_opt(ms).single())
+                .unwrap_or_else(Local::now)
+        };
+        let mut viewer = Self::new(
+            backend,
+            source,
+            to_local(stream.first_event_time),
+            to_local(stream.last_event_time),
+        );
+        // The range loading is not used for a single stream
+        viewer.pending.clear();
+        viewer.stream = Some(StreamCursor {
+            name: stream.name.clone(),
+            ..Default::default()
+        });
+        viewer
+    }
 
-        self.cloudwatch_client = Some(CloudWatchLogsClient::new(&aws_config));
-        self.load_logs().await?;
-        Ok(())
+    /// A viewer over the latest events of the source, however old they are.
+    /// The range is only known once they have been found.
+    pub fn recent(backend: Arc<dyn LogBackend>, source: LogSource, limit: RecentLimit) -> Self {
+        let now = Local::now();
+        let mut viewer = Self::new(backend, source, now - chrono::Duration::hours(1), now);
+        viewer.pending.clear();
+        viewer.recent = Some(limit);
+        viewer
+    }
+
+    pub fn stream_name(&self) -> Option<&str> {
+        self.stream.as_ref().map(|cursor| cursor.name.as_str())
     }
 
-    async fn load_logs(&mut self) -> Result<()> {
-        let client = self.cloudwatch_client.as_ref().unwrap();
-        let log_group_name = format!("/aws/lambda/{}", self.function_name);
+    /// Older events of the stream exist before the ones loaded so far.
+    pub fn has_older(&self) -> bool {
+        self.stream.as_ref().is_some_and(|cursor| !cursor.at_head)
+    }
 
-        let start_time = self.from_date.timestamp_millis();
-        let end_time = self.to_date.timestamp_millis();
+    fn query(&self, start_time: i64, end_time: i64) -> LogQuery {
+        LogQuery {
+            log_group_name: self.source.log_group_name.clone(),
+            start_time,
+            end_time,
+            filter_pattern: (!self.server_filter.trim().is_empty())
+                .then(|| self.server_filter.trim().to_string()),
+        }
+    }
 
-        let mut logs = Vec::new();
-        let mut next_token = None;
+    /// Loads the whole range before returning; used by the headless commands.
+    pub async fn load_logs(&mut self) -> Result<()> {
+        if let Some(limit) = self.recent {
+            let logs = find_recent_logs(
+                self.backend.as_ref(),
+                &self.source.log_group_name,
+                self.query(0, 0).filter_pattern,
+                limit,
+                Local::now().timestamp_millis(),
+            )
+            .await?;
+            self.handle_recent_logs(logs);
+            return Ok(());
+        }
+        let query = self.query(
+            self.from_date.timestamp_millis(),
+            self.to_date.timestamp_millis(),
+        );
+        let logs = fetch_range(self.backend.as_ref(), &query).await?;
 
-        loop {
-            let mut request = client
-                .filter_log_events()
-                .log_group_name(&log_group_name)
-                .start_time(start_time as i64)
-                .end_time(end_time as i64)
-                .limit(100);
+        self.seen_events = logs.iter().map(LogEvent::dedup_key).collect();
+        self.requests.rebuild(&logs);
+        *self.logs.lock().unwrap() = logs;
+        self.covered = vec![(query.start_time, query.end_time)];
+        self.pending.clear();
+        self.update_filter();
+        Ok(())
+    }
 
-            if let Some(token) = &next_token {
-                request = request.next_token(token);
+    /// Fetches up to `max_pages` more pages in the background, continuing
+    /// where the last fetch stopped. Each page is streamed back as an
+    /// `AppMessage::LogPage` and shown as soon as it arrives.
+    pub fn load_pages(&mut self, tx: &UnboundedSender<AppMessage>, max_pages: usize) {
+        if let Some(cursor) = &mut self.stream {
+            // New events may have been written since the tail was reached
+            cursor.at_tail = false;
+            let forward = cursor.forward_token.is_some();
+            self.auto_load = true;
+            self.load_stream_page(tx, forward);
+            return;
+        }
+        if let Some(limit) = self.recent {
+            self.find_recent_logs(tx, limit);
+            return;
+        }
+        if self.loading.is_some() || self.pending.is_empty() {
+            return;
+        }
+        self.auto_load = true;
+
+        let backend = Arc::clone(&self.backend);
+        let queries: Vec<LogQuery> = self
+            .pending
+            .iter()
+            .map(|&(start, end)| self.query(start, end))
+            .collect();
+        let mut next_token = self.next_token.clone();
+        let viewer_id = self.id;
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let mut queries = queries.iter().peekable();
+            let mut page_number = 0;
+            while let Some(query) = queries.peek() {
+                page_number += 1;
+                match backend.filter_log_events(query, next_token.take()).await {
+                    Ok(page) => {
+                        next_token = page.next_token;
+                        if next_token.is_none() {
+                            queries.next();
+                        }
+                        let done = queries.peek().is_none() || page_number >= max_pages;
+                        let message = AppMessage::LogPage {
+                            viewer_id,
+                            events: page.events,
+                            next_token: next_token.clone(),
+                            done,
+                        };
+                        if tx.send(message).is_err() || done {
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        let _ = tx.send(AppMessage::LogFetchFailed { viewer_id, error });
+                        break;
+                    }
+                }
             }
+        });
+
+        self.loading = Some(LoadingState::new("Loading logs", handle));
+    }
 
-            let response = request.send().await?;
+    fn find_recent_logs(&mut self, tx: &UnboundedSender<AppMessage>, limit: RecentLimit) {
+        if self.loading.is_some() {
+            return;
+        }
+        self.auto_load = true;
 
-            if let Some(events) = response.events {
-                logs.extend(events.into_iter().map(|e| {
-                    OutputLogEvent::builder()
-                        .timestamp(e.timestamp.unwrap_or(0))
-                        .message(e.message.unwrap_or(String::new()))
-                        .ingestion_time(e.ingestion_time.unwrap_or(0))
-                        .build()
-                }));
+        let backend = Arc::clone(&self.backend);
+        let log_group_name = self.source.log_group_name.clone();
+        let filter_pattern = self.query(0, 0).filter_pattern;
+        let viewer_id = self.id;
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let now = Local::now().timestamp_millis();
+            let result = find_recent_logs(
+                backend.as_ref(),
+                &log_group_name,
+                filter_pattern,
+                limit,
+                now,
+            )
+            .await;
+            let _ = tx.send(match result {
+                Ok(logs) => AppMessage::RecentLogs { viewer_id, logs },
+                Err(error) => AppMessage::LogFetchFailed { viewer_id, error },
+            });
+        });
+
+        self.loading = Some(LoadingState::new(
+            &format!("Finding the {}", limit.describe()),
+            handle,
+        ));
+    }
+
+    /// Shows the latest events found by `find_recent_logs`; their range
+    /// becomes the viewer's range.
+    pub fn handle_recent_logs(&mut self, logs: RecentLogs) {
+        let Some(limit) = self.recent.take() else {
+            return;
+        };
+        self.loading = None;
+        let (Some(from_date), Some(to_date)) = (
+            Local.timestamp_millis_opt(logs.start_time).single(),
+            Local.timestamp_millis_opt(logs.end_time).single(),
+        ) else {
+            return;
+        };
+
+        self.from_date = from_date;
+        self.to_date = to_date;
+        self.covered = vec![(logs.start_time, logs.end_time)];
+        self.range_changed = true;
+        self.status_message = Some(format!(
+            "The {}: {} – {}",
+            limit.describe(),
+            self.timezone.format(&from_date, "%Y-%m-%d %H:%M:%S"),
+            self.timezone.format(&to_date, "%Y-%m-%d %H:%M:%S")
+        ));
+        self.insert_logs(logs.events);
+        self.select_last();
+    }
+
+    /// Fetches the next pages once the selection gets close to the part of
+    /// the range not loaded yet, unless loading was cancelled or failed. A
+    /// single stream is also paged backwards near the start.
+    pub fn load_more_if_needed(&mut self, tx: &UnboundedSender<AppMessage>) {
+        if !self.auto_load {
+            return;
+        }
+
+        match &self.stream {
+            Some(cursor) => {
+                let (selected, total) = match &self.invocations {
+                    Some(invocations) => (invocations.selected, invocations.rows.len()),
+                    None => (self.selected_log.unwrap_or(0), self.filtered_logs.len()),
+                };
+                if !cursor.at_tail && total.saturating_sub(selected) <= PREFETCH_MARGIN {
+                    self.load_stream_page(tx, true);
+                } else if !cursor.at_head && selected < PREFETCH_MARGIN {
+                    self.load_stream_page(tx, false);
+                }
+            }
+            // Following watches the tail; the gap before it loads with Ctrl+L
+            None if !self.following
+                && self
+                    .events_before_unloaded()
+                    .is_some_and(|events| events <= PREFETCH_MARGIN) =>
+            {
+                self.load_pages(tx, ON_DEMAND_PAGES)
             }
+            None => {}
+        }
+    }
+
+    /// How many loaded events lie between the selection and the next part
+    /// of the range to fetch, if that part comes after the selection.
+    /// Events hidden by the filter count too, so a selective filter doesn't
+    /// page through the whole range.
+    fn events_before_unloaded(&self) -> Option<usize> {
+        let &(unloaded_start, _) = self.pending.first()?;
+        let selected = match &self.invocations {
+            Some(invocations) => invocations
+                .selected_invocation()
+                .map(|invocation| invocation.start_time),
+            None => self.get_selected_log().map(|log| log.timestamp),
+        }
+        .unwrap_or_else(|| self.from_date.timestamp_millis());
+        if selected >= unloaded_start {
+            return None;
+        }
+
+        let logs = self.logs.lock().unwrap();
+        let from = logs.partition_point(|log| log.timestamp < selected);
+        let to = logs.partition_point(|log| log.timestamp < unloaded_start);
+        Some(to - from)
+    }
+
+    /// Reads the next page of the stream forwards or backwards in the
+    /// background; the first read starts at the tail. The page arrives as
+    /// `AppMessage::StreamPage`.
+    fn load_stream_page(&mut self, tx: &UnboundedSender<AppMessage>, forward: bool) {
+        let Some(cursor) = &self.stream else {
+            return;
+        };
+        if self.loading.is_some() {
+            return;
+        }
+
+        let backend = Arc::clone(&self.backend);
+        let log_group_name = self.source.log_group_name.clone();
+        let stream_name = cursor.name.clone();
+        let token = if forward {
+            cursor.forward_token.clone()
+        } else {
+            cursor.backward_token.clone()
+        };
+        let viewer_id = self.id;
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            // Forward tokens must be used with start_from_head
+            let result = backend
+                .get_log_events(&log_group_name, &stream_name, token.clone(), forward)
+                .await;
+            let _ = tx.send(match result {
+                Ok(page) => AppMessage::StreamPage {
+                    viewer_id,
+                    forward,
+                    token,
+                    page,
+                },
+                Err(error) => AppMessage::LogFetchFailed { viewer_id, error },
+            });
+        });
+
+        self.loading = Some(LoadingState::new("Loading stream", handle));
+    }
+
+    /// Adds a page read with `token`; getting the same token back means that
+    /// direction has reached the end of the stream.
+    pub fn handle_stream_page(&mut self, forward: bool, token: Option<String>, page: StreamPage) {
+        let Some(cursor) = &mut self.stream else {
+            return;
+        };
+        self.loading = None;
+
+        // The first read sets up both directions from the tail
+        let first = cursor.forward_token.is_none() && cursor.backward_token.is_none();
+        if forward || first {
+            cursor.at_tail = page.next_forward_token.is_none() || page.next_forward_token == token;
+            cursor.forward_token = page.next_forward_token;
+        }
+        if !forward || first {
+            cursor.at_head =
+                page.next_backward_token.is_none() || page.next_backward_token == token;
+            cursor.backward_token = page.next_backward_token;
+        }
+
+        if forward || first {
+            self.append_logs(page.events, false);
+        } else {
+            self.insert_logs(page.events);
+        }
+        if first {
+            self.select_last();
+        }
+    }
+
+    pub fn edit_server_filter(&mut self) {
+        if self.stream.is_some() {
+            self.status_message =
+                Some("Server filters are not available for a single stream".to_string());
+            return;
+        }
+        self.server_filter_input = Some(self.server_filter.clone());
+    }
+
+    pub fn cancel_server_filter(&mut self) {
+        self.server_filter_input = None;
+    }
+
+    /// Applies the edited server filter, dropping loaded events and fetching
+    /// the range again if it changed.
+    pub fn apply_server_filter(&mut self, tx: &UnboundedSender<AppMessage>, max_pages: usize) {
+        let Some(input) = self.server_filter_input.take() else {
+            return;
+        };
+        if input.trim() == self.server_filter.trim() {
+            return;
+        }
+        self.server_filter = input;
+
+        // None of the loaded events can be kept
+        self.covered.clear();
+        self.set_range(self.from_date, self.to_date, tx, max_pages);
+    }
+
+    /// Moves the viewer to another range. Events already fetched for the
+    /// overlapping part are kept and only the rest is fetched.
+    fn set_range(
+        &mut self,
+        from_date: DateTime<Local>,
+        to_date: DateTime<Local>,
+        tx: &UnboundedSender<AppMessage>,
+        max_pages: usize,
+    ) {
+        let (start, end) = (from_date.timestamp_millis(), to_date.timestamp_millis());
+        let kept: Vec<(i64, i64)> = self
+            .covered
+            .iter()
+            .map(|&(covered_start, covered_end)| (covered_start.max(start), covered_end.min(end)))
+            .filter(|(kept_start, kept_end)| kept_start < kept_end)
+            .collect();
+
+        // A new ID makes pages still in flight for the old range stale
+        self.id = NEXT_VIEWER_ID.fetch_add(1, Ordering::Relaxed);
+        self.loading = None;
+        self.poll_in_flight = None;
+        self.next_token = None;
+        {
+            let mut logs = self.logs.lock().unwrap();
+            logs.retain(|log| {
+                kept.iter()
+                    .any(|&(kept_start, kept_end)| (kept_start..=kept_end).contains(&log.timestamp))
+            });
+            self.seen_events = logs.iter().map(LogEvent::dedup_key).collect();
+            self.requests.rebuild(&logs);
+        }
+        self.pending = missing_ranges((start, end), &kept);
+        self.covered = kept;
+        self.from_date = from_date;
+        self.to_date = to_date;
+
+        self.reapply_filter();
+        self.load_pages(tx, max_pages);
+    }
+
+    /// Changes the range from inside the viewer; the end is kept in the past
+    /// and following stops.
+    fn change_range(
+        &mut self,
+        from_date: DateTime<Local>,
+        to_date: DateTime<Local>,
+        tx: &UnboundedSender<AppMessage>,
+        max_pages: usize,
+    ) {
+        if self.stream.is_some() {
+            self.status_message =
+                Some("The range of a single stream cannot be changed".to_string());
+            return;
+        }
+        let to_date = to_date.min(Local::now());
+        if from_date >= to_date {
+            return;
+        }
 
-            next_token = response.next_token;
+        self.following = false;
+        self.expanded = false;
+        self.range_changed = true;
+        self.status_message = Some(format!(
+            "Range: {} – {}",
+            self.timezone.format(&from_date, "%Y-%m-%d %H:%M:%S"),
+            self.timezone.format(&to_date, "%Y-%m-%d %H:%M:%S")
+        ));
+        self.set_range(from_date, to_date, tx, max_pages);
+    }
+
+    /// The range was panned or zoomed since the viewer opened.
+    pub fn range_changed(&self) -> bool {
+        self.range_changed
+    }
+
+    /// Moves the range earlier or later by its own width.
+    pub fn shift_range(&mut self, later: bool, tx: &UnboundedSender<AppMessage>, max_pages: usize) {
+        let width = self.to_date - self.from_date;
+        let shift = if later {
+            // Not past the present
+            width.min(Local::now() - self.to_date)
+        } else {
+            -width
+        };
+        if shift <= chrono::Duration::zero() && later {
+            self.status_message = Some("Already showing the newest events".to_string());
+            return;
+        }
+        self.change_range(self.from_date + shift, self.to_date + shift, tx, max_pages);
+    }
+
+    /// Halves or doubles the range around the selected event, or around the
+    /// middle of the range if nothing is selected.
+    pub fn zoom(&mut self, zoom_in: bool, tx: &UnboundedSender<AppMessage>, max_pages: usize) {
+        let width = (self.to_date - self.from_date).num_milliseconds();
+        let half_width = if zoom_in {
+            (width / 4).max(MIN_RANGE_MS / 2)
+        } else {
+            width
+        };
+        let center = self
+            .get_selected_log()
+            .map_or(self.from_date.timestamp_millis() + width / 2, |log| {
+                log.timestamp
+            });
+        self.show_around(center, half_width, tx, max_pages);
+    }
+
+    /// Shows `minutes` before and after the selected event.
+    pub fn show_around_event(
+        &mut self,
+        minutes: i64,
+        tx: &UnboundedSender<AppMessage>,
+        max_pages: usize,
+    ) {
+        let Some(timestamp) = self.get_selected_log().map(|log| log.timestamp) else {
+            return;
+        };
+        self.show_around(timestamp, minutes.max(1) * 60_000, tx, max_pages);
+    }
+
+    fn show_around(
+        &mut self,
+        center: i64,
+        half_width: i64,
+        tx: &UnboundedSender<AppMessage>,
+        max_pages: usize,
+    ) {
+        let (Some(from_date), Some(to_date)) = (
+            Local.timestamp_millis_opt(center - half_width).single(),
+            Local.timestamp_millis_opt(center + half_width).single(),
+        ) else {
+            return;
+        };
+        self.change_range(from_date, to_date, tx, max_pages);
+    }
+
+    /// More pages exist beyond the ones loaded so far.
+    pub fn has_more(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn handle_page(&mut self, events: Vec<LogEvent>, next_token: Option<String>, done: bool) {
+        if let Some(loading) = &mut self.loading {
+            loading.record_page(events.len());
+        }
+        let newest = events.iter().map(|event| event.timestamp).max();
+        self.insert_logs(events);
+        if let Some(&(start, end)) = self.pending.first() {
             if next_token.is_none() {
-                break;
+                // The part is done, the next page starts the following one
+                self.pending.remove(0);
+                add_range(&mut self.covered, (start, end));
+            } else if let Some(newest) = newest {
+                // Pages come in time order, so the part is fetched up to here
+                add_range(&mut self.covered, (start, newest));
             }
         }
+        self.next_token = next_token;
+        if done {
+            self.loading = None;
+        }
+    }
 
-        *self.logs.lock().unwrap() = logs;
-        self.update_filter();
-        Ok(())
+    pub fn handle_fetch_error(&mut self) {
+        self.loading = None;
+        self.auto_load = false;
     }
 
-    pub fn update_filter(&mut self) {
-        let logs = self.logs.lock().unwrap();
+    /// Forgets a fetch or follow poll whose task panicked, so they don't
+    /// block the ones after it.
+    pub fn handle_task_panic(&mut self, task_id: Id) {
+        if self
+            .loading
+            .as_ref()
+            .is_some_and(|loading| loading.is_task(task_id))
+        {
+            self.handle_fetch_error();
+        }
+        if self.poll_in_flight.is_some_and(|(_, id)| id == task_id) {
+            self.poll_in_flight = None;
+            self.following = false;
+        }
+    }
 
-        if self.filter_input.is_empty() {
-            self.filtered_logs = logs.clone();
+    /// Cancels an in-flight fetch, keeping whatever has been loaded so far.
+    /// Returns `false` if nothing was loading.
+    pub fn cancel_loading(&mut self) -> bool {
+        if self.loading.take().is_none() {
+            return false;
+        }
+        self.auto_load = false;
+        self.status_message = Some(format!(
+            "Loading cancelled, showing {} events",
+            self.logs.lock().unwrap().len()
+        ));
+        true
+    }
+
+    pub fn toggle_follow(&mut self) {
+        if self.stream.is_some() {
+            self.status_message =
+                Some("Follow is not available for a single stream, use Ctrl+L".to_string());
+            return;
+        }
+        self.following = !self.following;
+        self.last_poll = None;
+        if self.following {
+            self.select_last();
+        }
+    }
+
+    /// Event counts of the shown events over the viewer's range.
+    pub fn histogram(&self) -> Histogram {
+        Histogram::new(
+            &self.filtered_logs,
+            self.from_date.timestamp_millis(),
+            self.to_date.timestamp_millis(),
+            TIMELINE_BUCKETS,
+        )
+    }
+
+    /// Gives the timeline focus, starting at the selected event's bucket, or
+    /// takes it away.
+    pub fn toggle_timeline(&mut self) {
+        if self.timeline_cursor.take().is_some() {
+            return;
+        }
+        let histogram = self.histogram();
+        self.timeline_cursor = Some(
+            self.get_selected_log()
+                .and_then(|log| histogram.bucket_of(log.timestamp))
+                .unwrap_or(TIMELINE_BUCKETS - 1),
+        );
+    }
+
+    pub fn move_timeline_cursor(&mut self, offset: isize) {
+        if let Some(cursor) = self.timeline_cursor.as_mut() {
+            *cursor = cursor
+                .saturating_add_signed(offset)
+                .min(TIMELINE_BUCKETS - 1);
+        }
+    }
+
+    /// Selects the first event of the timeline bucket and leaves the
+    /// timeline.
+    pub fn jump_to_bucket(&mut self) {
+        let Some(cursor) = self.timeline_cursor.take() else {
+            return;
+        };
+        let (start, _) = self.histogram().bucket_range(cursor);
+        self.expanded = false;
+
+        if let Some(invocations) = self.invocations.as_mut() {
+            invocations.select_time(start);
+            return;
+        }
+        let first_after = self
+            .filtered_logs
+            .iter()
+            .position(|log| log.timestamp >= start);
+        self.selected_log = first_after.or(self.filtered_logs.len().checked_sub(1));
+    }
+
+    /// Narrows the range to the timeline bucket and fetches it.
+    pub fn zoom_to_bucket(&mut self, tx: &UnboundedSender<AppMessage>, max_pages: usize) {
+        let Some(cursor) = self.timeline_cursor.take() else {
+            return;
+        };
+        let (start, end) = self.histogram().bucket_range(cursor);
+        self.show_around((start + end) / 2, (end - start) / 2, tx, max_pages);
+    }
+
+    /// Following is paused while the selection is away from the newest event.
+    pub fn is_follow_paused(&self) -> bool {
+        self.following
+            && self
+                .selected_log
+                .is_some_and(|i| i + 1 < self.filtered_logs.len())
+    }
+
+    fn should_poll(&self) -> bool {
+        // Older parts of the range may still be loading; polls only cover the tail
+        self.following
+            && self.recent.is_none()
+            && self.poll_in_flight.is_none()
+            && self
+                .last_poll
+                .is_none_or(|last| last.elapsed() >= FOLLOW_POLL_INTERVAL)
+    }
+
+    /// When following, fetches events newer than the last seen one in the
+    /// background; results arrive as `AppMessage::FollowEvents`.
+    pub fn poll_if_due(&mut self, tx: &UnboundedSender<AppMessage>) {
+        if !self.should_poll() {
+            return;
+        }
+        let now = Local::now().timestamp_millis();
+        self.last_poll = Some(Instant::now());
+
+        let start = (self.follow_start() - FOLLOW_OVERLAP_MS).max(now - FOLLOW_MAX_POLL_MS);
+        let query = self.query(start, now);
+        let backend = Arc::clone(&self.backend);
+        let viewer_id = self.id;
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let result = fetch_range(backend.as_ref(), &query).await;
+            let _ = tx.send(AppMessage::FollowEvents { viewer_id, result });
+        });
+        self.poll_in_flight = Some(((start, now), handle.id()));
+    }
+
+    /// Where the next poll starts: the newest event when the end of the
+    /// range is loaded, so late arriving events are still found, or else
+    /// the end of the range, leaving the older parts to the pagination.
+    fn follow_start(&self) -> i64 {
+        let end = self.to_date.timestamp_millis();
+        let tail_start = self
+            .covered
+            .last()
+            .filter(|&&(_, covered_end)| covered_end >= end)
+            .map(|&(start, _)| start);
+        let newest = self.logs.lock().unwrap().last().map(|log| log.timestamp);
+        match (tail_start, newest) {
+            (Some(tail_start), Some(newest)) if newest >= tail_start => newest,
+            _ => end,
+        }
+    }
+
+    /// Appends newly polled events, keeping the current selection and filter.
+    pub fn handle_follow_events(&mut self, result: Result<Vec<LogEvent>>) {
+        let Some(((polled_from, polled_until), _)) = self.poll_in_flight.take() else {
+            return;
+        };
+        match result {
+            Ok(mut events) => {
+                // The polled tail is covered, so the pagination doesn't fetch
+                // it, but a range that ended long ago leaves a gap to page
+                let previous_end = self.to_date.timestamp_millis();
+                if let Some(to_date) = Local.timestamp_millis_opt(polled_until).single() {
+                    self.to_date = to_date;
+                }
+                if polled_from > previous_end {
+                    self.pending.push((previous_end, polled_from));
+                    add_range(&mut self.covered, (polled_from, polled_until));
+                } else {
+                    add_range(&mut self.covered, (previous_end, polled_until));
+                }
+                events.sort_by_key(|event| event.timestamp);
+                self.append_logs(events, true);
+            }
+            Err(e) => {
+                let error = AwsError::from_anyhow(&e);
+                self.status_message = Some(format!(
+                    "Follow failed ({}), retrying: {}",
+                    error.title(),
+                    error
+                ));
+            }
+        }
+    }
+
+    /// Adds polled events after the loaded ones. Late ingested events can be
+    /// older than the newest loaded one and are sorted in.
+    fn append_logs(&mut self, mut events: Vec<LogEvent>, follow: bool) {
+        events.retain(|event| self.seen_events.insert(event.dedup_key()));
+        let Some(oldest) = events.first().map(|event| event.timestamp) else {
+            return;
+        };
+        let was_at_end = !self.is_follow_paused();
+
+        let mut logs = self.logs.lock().unwrap();
+        if logs.last().is_some_and(|newest| oldest < newest.timestamp) {
+            logs.extend(events);
+            logs.sort_by_key(|log| log.timestamp);
+            // Requests are followed from the first event on
+            self.requests.rebuild(&logs);
+            drop(logs);
+            self.reapply_filter();
+        } else if self.request_filter.is_some() || self.problem_filter != ProblemFilter::All {
+            // Earlier lines of a request can change with its later ones
+            self.requests.add(&events);
+            logs.extend(events);
+            drop(logs);
+            self.reapply_filter();
         } else {
-            let filter_lower = self.filter_input.to_lowercase();
-            let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
+            self.requests.add(&events);
+            let keywords = filter_keywords(&self.filter_input);
+            self.filtered_logs.extend(
+                events
+                    .iter()
+                    .filter(|log| matches_keywords(&log.message, &keywords))
+                    .cloned(),
+            );
+            logs.extend(events);
+            drop(logs);
+            self.refresh_invocations();
+        }
 
-            self.filtered_logs = logs
-                .iter()
-                .filter(|log| {
-                    if let Some(message) = log.message.as_ref() {
-                        let message_lower = message.to_lowercase();
-                        keywords
-                            .iter()
-                            .all(|&keyword| message_lower.contains(keyword))
-                    } else {
-                        false
-                    }
-                })
-                .cloned()
-                .collect();
+        if follow && was_at_end && !self.expanded {
+            self.select_last();
+        } else if self.selected_log.is_none() && !self.filtered_logs.is_empty() {
+            self.selected_log = Some(0);
         }
+    }
+
+    /// Inserts events in time order, keeping the selection on the same
+    /// event. The events must not interleave with the loaded ones, as for a
+    /// page of another part of the range or an older page of a stream.
+    fn insert_logs(&mut self, mut events: Vec<LogEvent>) {
+        events.retain(|event| self.seen_events.insert(event.dedup_key()));
+        let Some(first) = events.first().map(|event| event.timestamp) else {
+            return;
+        };
+        // Pages loading while following shouldn't pause it
+        let follow_last = self.following && !self.is_follow_paused();
+
+        let keywords = filter_keywords(&self.filter_input);
+        let matching: Vec<LogEvent> = events
+            .iter()
+            .filter(|log| matches_keywords(&log.message, &keywords))
+            .cloned()
+            .collect();
+        {
+            let mut logs = self.logs.lock().unwrap();
+            let at = logs.partition_point(|log| log.timestamp <= first);
+            if at == logs.len() {
+                logs.extend(events);
+                self.requests.add(&logs[at..]);
+            } else {
+                logs.splice(at..at, events);
+                // Requests are followed from the first event on
+                self.requests.rebuild(&logs);
+            }
+        }
+
+        if self.request_filter.is_some() || self.problem_filter != ProblemFilter::All {
+            self.reapply_filter();
+            if follow_last {
+                self.select_last();
+            }
+            return;
+        }
+
+        let at = self
+            .filtered_logs
+            .partition_point(|log| log.timestamp <= first);
+        self.selected_log = match self.selected_log {
+            Some(i) if at <= i => Some(i + matching.len()),
+            Some(i) => Some(i),
+            None => (!matching.is_empty()).then_some(0),
+        };
+        self.filtered_logs.splice(at..at, matching);
+        self.refresh_invocations();
+        if follow_last {
+            self.select_last();
+        }
+    }
+
+    fn select_last(&mut self) {
+        self.selected_log = self.filtered_logs.len().checked_sub(1);
+    }
+
+    pub fn update_filter(&mut self) {
+        let logs = self.logs.lock().unwrap();
+        let keywords = filter_keywords(&self.filter_input);
+
+        self.filtered_logs = logs
+            .iter()
+            .filter(|log| {
+                self.request_filter
+                    .as_deref()
+                    .is_none_or(|request_id| self.requests.request_id(log) == Some(request_id))
+            })
+            .filter(|log| self.problem_filter.matches(self.requests.problems(log)))
+            .filter(|log| matches_keywords(&log.message, &keywords))
+            .cloned()
+            .collect();
+        drop(logs);
+        self.refresh_invocations();
 
         // Reset selection when filter changes
         self.selected_log = if self.filtered_logs.is_empty() {
@@ -137,6 +1038,118 @@ impl LogViewer {
         self.expanded = false;
     }
 
+    /// Cycles the quick filter between all events, failed invocations and
+    /// cold starts.
+    pub fn cycle_problem_filter(&mut self) {
+        self.problem_filter = self.problem_filter.next();
+        self.reapply_filter();
+        self.status_message = Some(format!("Showing: {}", self.problem_filter.display_name()));
+    }
+
+    /// The cold start, timeout, crash and OOM conditions of the event's
+    /// invocation.
+    pub fn problems(&self, event: &LogEvent) -> Problems {
+        self.requests.problems(event)
+    }
+
+    /// Filters again, keeping the selection on the same event.
+    fn reapply_filter(&mut self) {
+        let selected = self.get_selected_log().map(LogEvent::dedup_key);
+        let expanded = self.expanded;
+        self.update_filter();
+        if let Some(key) = selected {
+            self.select_event(&key);
+        }
+        self.expanded = expanded;
+    }
+
+    fn select_event(&mut self, key: &str) {
+        if let Some(i) = self
+            .filtered_logs
+            .iter()
+            .position(|log| log.dedup_key() == key)
+        {
+            self.selected_log = Some(i);
+        }
+    }
+
+    /// Regroups the loaded events for the invocation view and stats panel,
+    /// if shown.
+    fn refresh_invocations(&mut self) {
+        if self.invocations.is_none() && self.stats.is_none() {
+            return;
+        }
+        let grouped = group_invocations(&self.logs.lock().unwrap());
+
+        if let Some(stats) = self.stats.as_mut() {
+            *stats = InvocationStats::from_invocations(&grouped);
+        }
+        if let Some(invocations) = self.invocations.as_mut() {
+            let keywords = filter_keywords(&self.filter_input);
+            invocations.rebuild(&grouped, |invocation| {
+                self.problem_filter.matches(invocation.problems)
+                    && invocation
+                        .events
+                        .iter()
+                        .any(|log| matches_keywords(&log.message, &keywords))
+            });
+        }
+    }
+
+    pub fn toggle_stats(&mut self) {
+        if self.stats.take().is_none() {
+            self.stats = Some(InvocationStats::default());
+            self.refresh_invocations();
+        }
+    }
+
+    /// Switches between the event list and one row per invocation.
+    pub fn toggle_invocations(&mut self) {
+        self.expanded = false;
+        if self.invocations.take().is_some() {
+            return;
+        }
+        self.clear_request_filter();
+        self.invocations = Some(InvocationList::default());
+        self.refresh_invocations();
+    }
+
+    /// Shows every line of the selected event's request, including those
+    /// in other pages. The keyword filter is put aside until
+    /// `clear_request_filter`.
+    pub fn show_request(&mut self) {
+        let Some(key) = self.get_selected_log().map(LogEvent::dedup_key) else {
+            return;
+        };
+        let Some(request_id) = self
+            .get_selected_log()
+            .and_then(|log| self.requests.request_id(log))
+            .map(String::from)
+        else {
+            self.status_message = Some("No request ID found for this event".to_string());
+            return;
+        };
+
+        if self.request_filter.is_none() {
+            self.filter_before_request = std::mem::take(&mut self.filter_input);
+        }
+        self.request_filter = Some(request_id);
+        self.invocations = None;
+        self.update_filter();
+        self.select_event(&key);
+    }
+
+    /// Goes back to all events, keeping the selected one. Returns `false` if
+    /// no request was shown.
+    pub fn clear_request_filter(&mut self) -> bool {
+        if self.request_filter.take().is_none() {
+            return false;
+        }
+        self.filter_input = std::mem::take(&mut self.filter_before_request);
+        self.reapply_filter();
+        true
+    }
+
     pub fn scroll_up(&mut self) {
         if self.expanded {
             self.scroll_position = self.scroll_position.saturating_sub(1);
@@ -145,46 +1158,85 @@ impl LogViewer {
 
     pub fn scroll_down(&mut self) {
         if let Some(log) = self.get_selected_log() {
-            if let Some(message) = &log.message {
-                let line_count =
-                    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
-                        // Count JSON formatted lines
-                        format_json(&json_value, 0).len()
-                    } else {
-                        // Count regular message lines
-                        message.lines().count()
-                    };
-                self.scroll_position = self
-                    .scroll_position
-                    .saturating_add(1)
-                    .min(line_count.saturating_sub(1));
-            }
+            let message = &log.message;
+            let line_count =
+                if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
+                    // Count JSON formatted lines
+                    format_json(&json_value, 0).len()
+                } else {
+                    // Count regular message lines
+                    message.lines().count()
+                };
+            self.scroll_position = self
+                .scroll_position
+                .saturating_add(1)
+                .min(line_count.saturating_sub(1));
         }
     }
 
-    pub fn update_scroll(&mut self, visible_height: usize) {
-        if let Some(selected) = self.selected_log {
-            // Keep selection in the middle of the visible area when possible
-            let middle = visible_height / 2;
+    pub fn open_export_menu(&mut self) {
+        self.export_menu = Some(0);
+    }
 
-            if selected >= middle {
-                self.scroll_offset = selected.saturating_sub(middle);
-            } else {
-                self.scroll_offset = 0;
-            }
+    pub fn close_export_menu(&mut self) {
+        self.export_menu = None;
+    }
+
+    pub fn next_export_format(&mut self) {
+        if let Some(current) = self.export_menu {
+            self.export_menu = Some((current + 1) % ExportFormat::all().len());
+        }
+    }
 
-            // Don't scroll past the end
-            let max_scroll = self.filtered_logs.len().saturating_sub(visible_height);
-            self.scroll_offset = self.scroll_offset.min(max_scroll);
+    pub fn previous_export_format(&mut self) {
+        if let Some(current) = self.export_menu {
+            let count = ExportFormat::all().len();
+            self.export_menu = Some((current + count - 1) % count);
         }
     }
 
+    /// Writes the filtered logs in the format chosen in the export menu to the
+    /// current directory, reporting the outcome in `status_message`.
+    pub fn export_selected(&mut self) {
+        let Some(format) = self
+            .export_menu
+            .take()
+            .and_then(|i| ExportFormat::all().get(i).copied())
+        else {
+            return;
+        };
+
+        let base_name = format!(
+            "{}_{}_{}",
+            self.source.file_stem(),
+            self.timezone.format(&self.from_date, "%Y%m%dT%H%M"),
+            self.timezone.format(&self.to_date, "%Y%m%dT%H%M")
+        );
+        let result = std::env::current_dir()
+            .map_err(anyhow::Error::from)
+            .and_then(|dir| {
+                export_to_file(&self.filtered_logs, format, self.timezone, &dir, &base_name)
+            });
+
+        self.status_message = Some(match result {
+            Ok(path) => format!(
+                "Exported {} events to {}",
+                self.filtered_logs.len(),
+                path.display()
+            ),
+            Err(e) => format!("Export failed: {}", e),
+        });
+    }
+
     pub fn toggle_expand(&mut self) {
         self.expanded = !self.expanded;
         self.scroll_offset = 0;
     }
 
-    pub fn get_selected_log(&self) -> Option<&OutputLogEvent> {
+    pub fn get_selected_log(&self) -> Option<&LogEvent> {
+        if let Some(invocations) = &self.invocations {
+            return invocations.selected_event();
+        }
         self.selected_log.and_then(|i| self.filtered_logs.get(i))
     }
 
@@ -196,16 +1248,14 @@ impl LogViewer {
 
     pub fn page_down(&mut self) {
         if let Some(log) = self.get_selected_log() {
-            if let Some(message) = &log.message {
-                let line_count =
-                    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
-                        format_json(&json_value, 0).len()
-                    } else {
-                        message.lines().count()
-                    };
-                self.scroll_position =
-                    (self.scroll_position + 10).min(line_count.saturating_sub(1));
-            }
+            let message = &log.message;
+            let line_count =
+                if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(message) {
+                    format_json(&json_value, 0).len()
+                } else {
+                    message.lines().count()
+                };
+            self.scroll_position = (self.scroll_position + 10).min(line_count.saturating_sub(1));
         }
     }
 
@@ -258,3 +1308,390 @@ impl LogViewer {
         }
     }
 }
+
+fn filter_keywords(filter_input: &str) -> Vec<String> {
+    filter_input
+        .to_lowercase()
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+fn matches_keywords(message: &str, keywords: &[String]) -> bool {
+    if keywords.is_empty() {
+        return true;
+    }
+    let message_lower = message.to_lowercase();
+    keywords
+        .iter()
+        .all(|keyword| message_lower.contains(keyword.as_str()))
+}
+
+/// Adds `range` to sorted, non-overlapping `ranges`, merging where they
+/// touch.
+fn add_range(ranges: &mut Vec<(i64, i64)>, range: (i64, i64)) {
+    ranges.push(range);
+    ranges.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+    for &(start, end) in ranges.iter() {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    *ranges = merged;
+}
+
+/// The parts of `range` not in the sorted, non-overlapping `covered`.
+fn missing_ranges(range: (i64, i64), covered: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut missing = Vec::new();
+    let mut start = range.0;
+    for &(covered_start, covered_end) in covered {
+        if covered_start >= range.1 {
+            break;
+        }
+        if covered_start > start {
+            missing.push((start, covered_start));
+        }
+        start = start.max(covered_end);
+    }
+    if start < range.1 {
+        missing.push((start, range.1));
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    type Range = (i64, i64);
+
+    const FUNCTION: &str = "orders-prod-processor";
+
+    /// An event every 10 seconds over the last hour, every third an error.
+    fn hour_of_events(now: i64) -> Vec<LogEvent> {
+        (0..360)
+            .map(|i| {
+                let timestamp = now - 3_600_000 + i * 10_000;
+                LogEvent {
+                    event_id: Some(i.to_string()),
+                    log_stream_name: Some("stream".to_string()),
+                    timestamp,
+                    ingestion_time: timestamp,
+                    message: if i % 3 == 0 {
+                        format!("ERROR request {} failed", i)
+                    } else {
+                        format!("INFO request {}", i)
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// A viewer over the first half of the hour.
+    fn viewer(events: Vec<LogEvent>, now: DateTime<Local>) -> LogViewer {
+        let backend =
+            FakeBackend::new().with_events(&LogSource::lambda(FUNCTION).log_group_name, events);
+        LogViewer::new(
+            Arc::new(backend),
+            LogSource::lambda(FUNCTION),
+            now - chrono::Duration::hours(1),
+            now - chrono::Duration::minutes(30),
+        )
+    }
+
+    /// Hands the viewer its messages until nothing is in flight.
+    async fn run(viewer: &mut LogViewer, rx: &mut UnboundedReceiver<AppMessage>) {
+        while viewer.loading.is_some() || viewer.poll_in_flight.is_some() {
+            match rx.recv().await.unwrap() {
+                AppMessage::LogPage {
+                    viewer_id,
+                    events,
+                    next_token,
+                    done,
+                } if viewer_id == viewer.id => viewer.handle_page(events, next_token, done),
+                AppMessage::FollowEvents { viewer_id, result } if viewer_id == viewer.id => {
+                    viewer.handle_follow_events(result)
+                }
+                AppMessage::LogFetchFailed { error, .. } => panic!("{:#}", error),
+                _ => {}
+            }
+        }
+    }
+
+    fn timestamps(logs: &[LogEvent]) -> Vec<i64> {
+        logs.iter().map(|log| log.timestamp).collect()
+    }
+
+    #[tokio::test]
+    async fn loads_and_filters_the_range() {
+        let now = Local::now();
+        let mut viewer = viewer(hour_of_events(now.timestamp_millis()), now);
+        viewer.load_logs().await.unwrap();
+        // Both ends of the half hour are included
+        assert_eq!(viewer.filtered_logs.len(), 181);
+        assert_eq!(viewer.selected_log, Some(0));
+
+        viewer.filter_input = "error failed".to_string();
+        viewer.update_filter();
+        assert_eq!(viewer.filtered_logs.len(), 61);
+        assert!(viewer
+            .filtered_logs
+            .iter()
+            .all(|log| log.message.starts_with("ERROR")));
+    }
+
+    #[tokio::test]
+    async fn loads_the_range_in_pages() {
+        let now = Local::now();
+        let events = hour_of_events(now.timestamp_millis());
+        let mut viewer = viewer(events.clone(), now);
+        let in_range: Vec<LogEvent> = events
+            .into_iter()
+            .filter(|event| event.timestamp <= viewer.to_date.timestamp_millis())
+            .collect();
+        let (tx, mut rx) = unbounded_channel();
+
+        viewer.load_pages(&tx, 1);
+        run(&mut viewer, &mut rx).await;
+        assert_eq!(viewer.filtered_logs.len(), 100);
+        assert!(viewer.has_more());
+        assert_eq!(viewer.selected_log, Some(0));
+
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+        assert!(!viewer.has_more());
+        assert_eq!(timestamps(&viewer.filtered_logs), timestamps(&in_range));
+        assert_eq!(viewer.selected_log, Some(0));
+    }
+
+    #[tokio::test]
+    async fn refetches_with_a_server_filter() {
+        let now = Local::now();
+        let mut viewer = viewer(hour_of_events(now.timestamp_millis()), now);
+        let (tx, mut rx) = unbounded_channel();
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+
+        viewer.edit_server_filter();
+        viewer.server_filter_input = Some("ERROR".to_string());
+        viewer.apply_server_filter(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+        assert_eq!(viewer.logs.lock().unwrap().len(), 61);
+        assert!(viewer
+            .filtered_logs
+            .iter()
+            .all(|log| log.message.starts_with("ERROR")));
+    }
+
+    #[tokio::test]
+    async fn following_appends_newer_events() {
+        let now = Local::now();
+        let events = hour_of_events(now.timestamp_millis());
+        let mut viewer = viewer(events.clone(), now);
+        let (tx, mut rx) = unbounded_channel();
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+
+        // The range ended long ago, so the first poll only fetches the last
+        // minutes and leaves the gap to the pagination
+        viewer.toggle_follow();
+        viewer.poll_if_due(&tx);
+        run(&mut viewer, &mut rx).await;
+        let gap_end = viewer.to_date.timestamp_millis() - FOLLOW_MAX_POLL_MS;
+        let polled: Vec<i64> = timestamps(&events)
+            .into_iter()
+            .filter(|&timestamp| {
+                timestamp <= now.timestamp_millis() - 30 * 60_000 || timestamp >= gap_end
+            })
+            .collect();
+        assert_eq!(timestamps(&viewer.filtered_logs), polled);
+        assert_eq!(viewer.selected_log, Some(polled.len() - 1));
+        assert!(viewer.to_date >= now);
+        assert!(viewer.has_more());
+
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+        assert_eq!(timestamps(&viewer.filtered_logs), timestamps(&events));
+        assert_eq!(viewer.selected_log, Some(events.len() - 1));
+        assert!(!viewer.is_follow_paused());
+        assert!(!viewer.has_more());
+    }
+
+    #[tokio::test]
+    async fn sorts_in_late_events() {
+        let now = Local::now();
+        let events = hour_of_events(now.timestamp_millis());
+        let mut viewer = viewer(events.clone(), now);
+        let (tx, mut rx) = unbounded_channel();
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+        viewer.toggle_follow();
+
+        // Polled up to now, with one event ingested late for a second the
+        // previous poll covered
+        let newest = viewer.filtered_logs.last().unwrap().timestamp;
+        let late = LogEvent {
+            event_id: Some("late".to_string()),
+            timestamp: newest - 5_000,
+            message: "ERROR late".to_string(),
+            ..events[0].clone()
+        };
+        let new = LogEvent {
+            event_id: Some("new".to_string()),
+            timestamp: newest + 1_000,
+            message: "INFO new".to_string(),
+            ..events[0].clone()
+        };
+        viewer.poll_in_flight = Some((
+            (newest - FOLLOW_OVERLAP_MS, newest + 2_000),
+            spawn(async {}).id(),
+        ));
+        viewer.handle_follow_events(Ok(vec![late, new]));
+
+        let shown = timestamps(&viewer.filtered_logs);
+        assert!(shown.is_sorted());
+        assert_eq!(
+            shown[shown.len() - 3..],
+            [newest - 5_000, newest, newest + 1_000]
+        );
+        assert_eq!(viewer.selected_log, Some(shown.len() - 1));
+    }
+
+    #[tokio::test]
+    async fn filters_by_problems() {
+        let now = Local::now();
+        let start = now.timestamp_millis() - 50 * 60_000;
+        let report = |request_id: &str, extra: &str| {
+            format!(
+                "REPORT RequestId: {}\tDuration: 1.00 ms\tBilled Duration: 2 ms\tMemory Size: 128 MB\tMax Memory Used: 60 MB{}\t",
+                request_id, extra
+            )
+        };
+        let (cold, timed_out, fine) = (
+            "11111111-1111-4111-8111-111111111111",
+            "22222222-2222-4222-8222-222222222222",
+            "33333333-3333-4333-8333-333333333333",
+        );
+        let messages = [
+            format!("START RequestId: {} Version: $LATEST", cold),
+            format!("END RequestId: {}", cold),
+            report(cold, "\tInit Duration: 200.00 ms"),
+            format!("START RequestId: {} Version: $LATEST", timed_out),
+            "waiting for the payment provider".to_string(),
+            format!(
+                "2026-10-15T12:00:03.005Z {} Task timed out after 3.00 seconds",
+                timed_out
+            ),
+            format!("END RequestId: {}", timed_out),
+            report(timed_out, "\tStatus: timeout"),
+            format!("START RequestId: {} Version: $LATEST", fine),
+            format!("END RequestId: {}", fine),
+            report(fine, ""),
+        ];
+        let events = messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, message)| LogEvent {
+                event_id: Some(i.to_string()),
+                log_stream_name: Some("stream".to_string()),
+                timestamp: start + i as i64 * 1_000,
+                ingestion_time: start + i as i64 * 1_000,
+                message,
+            })
+            .collect();
+        let mut viewer = viewer(events, now);
+        viewer.load_logs().await.unwrap();
+        let ids = |viewer: &LogViewer| -> Vec<usize> {
+            viewer
+                .filtered_logs
+                .iter()
+                .map(|log| log.event_id.as_deref().unwrap().parse().unwrap())
+                .collect()
+        };
+        assert_eq!(ids(&viewer).len(), 11);
+
+        viewer.cycle_problem_filter();
+        assert_eq!(viewer.problem_filter, ProblemFilter::Failures);
+        assert_eq!(ids(&viewer), [3, 4, 5, 6, 7]);
+
+        viewer.cycle_problem_filter();
+        assert_eq!(viewer.problem_filter, ProblemFilter::ColdStarts);
+        assert_eq!(ids(&viewer), [0, 1, 2]);
+
+        viewer.cycle_problem_filter();
+        assert_eq!(viewer.problem_filter, ProblemFilter::All);
+        assert_eq!(ids(&viewer).len(), 11);
+    }
+
+    #[tokio::test]
+    async fn loads_again_after_a_fetch_panicked() {
+        let now = Local::now();
+        let mut viewer = viewer(hour_of_events(now.timestamp_millis()), now);
+        let (tx, mut rx) = unbounded_channel();
+        let panicked = spawn(async {});
+        let task_id = panicked.id();
+        viewer.loading = Some(LoadingState::new("Loading logs", panicked));
+
+        viewer.load_pages(&tx, 10);
+        assert!(viewer.filtered_logs.is_empty());
+        viewer.handle_task_panic(task_id);
+        assert!(viewer.loading.is_none());
+        viewer.load_pages(&tx, 10);
+        run(&mut viewer, &mut rx).await;
+        assert_eq!(viewer.filtered_logs.len(), 181);
+    }
+
+    #[test]
+    fn adds_ranges() {
+        // (covered before, added, covered after)
+        let cases: [(&[Range], Range, &[Range]); 6] = [
+            (&[], (10, 20), &[(10, 20)]),
+            (&[(10, 20)], (30, 40), &[(10, 20), (30, 40)]),
+            (&[(30, 40)], (10, 20), &[(10, 20), (30, 40)]),
+            (&[(10, 20)], (15, 30), &[(10, 30)]),
+            (&[(10, 20)], (20, 30), &[(10, 30)]),
+            (&[(10, 20), (30, 40)], (12, 35), &[(10, 40)]),
+        ];
+        for (covered, added, expected) in cases {
+            let mut ranges = covered.to_vec();
+            add_range(&mut ranges, added);
+            assert_eq!(ranges, expected, "{:?} + {:?}", covered, added);
+        }
+
+        let mut ranges = vec![(10, 40)];
+        add_range(&mut ranges, (20, 30));
+        assert_eq!(ranges, [(10, 40)]);
+    }
+
+    #[test]
+    fn finds_missing_ranges() {
+        // (range, covered, missing)
+        let cases: [(Range, &[Range], &[Range]); 8] = [
+            ((10, 50), &[], &[(10, 50)]),
+            ((10, 50), &[(0, 60)], &[]),
+            ((10, 50), &[(20, 30)], &[(10, 20), (30, 50)]),
+            ((10, 50), &[(0, 20), (40, 60)], &[(20, 40)]),
+            ((10, 50), &[(10, 20), (20, 30)], &[(30, 50)]),
+            ((10, 50), &[(0, 5), (60, 70)], &[(10, 50)]),
+            (
+                (10, 50),
+                &[(20, 30), (60, 70), (80, 90)],
+                &[(10, 20), (30, 50)],
+            ),
+            ((10, 50), &[(50, 60)], &[(10, 50)]),
+        ];
+        for (range, covered, expected) in cases {
+            assert_eq!(
+                missing_ranges(range, covered),
+                expected,
+                "{:?} minus {:?}",
+                range,
+                covered
+            );
+        }
+    }
+}
@@ -1,24 +1,97 @@
 pub mod date_selection;
+pub mod error_popup;
 pub mod function_selection;
+pub mod insights;
+pub mod invocation_list;
+pub mod loading;
+pub mod log_group_selection;
 pub mod log_viewer;
 pub mod profile_selection;
+pub mod stream_selection;
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::backend::{
+    Backend, FunctionInfo, LogEvent, LogGroupInfo, LogStreamInfo, QueryResults, StreamPage,
+};
+use crate::recent::RecentLogs;
+use function_selection::FunctionSelection;
 
 #[derive(Debug, PartialEq)]
 pub enum AppState {
     ProfileSelection,
     FunctionList,
+    LogGroupList,
     DateSelection,
+    StreamList,
     LogViewer,
+    Insights,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum FocusedPanel {
+    #[default]
     Left,
     Right,
 }
 
-impl Default for FocusedPanel {
-    fn default() -> Self {
-        Self::Left
-    }
+/// Results streamed back from background tasks to the event loop.
+///
+/// Log messages carry the id of the `LogViewer` that started the fetch so
+/// results for a viewer that has since been closed are dropped.
+pub enum AppMessage {
+    ProfileLoaded(Result<(Backend, Box<FunctionSelection>)>),
+    FunctionsLastLogged(Result<HashMap<String, i64>>),
+    FunctionTags(Result<HashMap<String, BTreeMap<String, String>>>),
+    FunctionsRefreshed(Result<Vec<FunctionInfo>>),
+    LogGroupsLoaded {
+        prefix: String,
+        result: Result<Vec<LogGroupInfo>>,
+    },
+    LogPage {
+        viewer_id: u64,
+        events: Vec<LogEvent>,
+        next_token: Option<String>,
+        done: bool,
+    },
+    RecentLogs {
+        viewer_id: u64,
+        logs: RecentLogs,
+    },
+    LogStreamsLoaded {
+        log_group_name: String,
+        result: Result<Vec<LogStreamInfo>>,
+    },
+    StreamPage {
+        viewer_id: u64,
+        forward: bool,
+        token: Option<String>,
+        page: StreamPage,
+    },
+    LogFetchFailed {
+        viewer_id: u64,
+        error: anyhow::Error,
+    },
+    FollowEvents {
+        viewer_id: u64,
+        result: Result<Vec<LogEvent>>,
+    },
+    QueryStarted {
+        view_id: u64,
+        query_id: String,
+    },
+    QueryResults {
+        view_id: u64,
+        results: QueryResults,
+    },
+    QueryFailed {
+        view_id: u64,
+        error: anyhow::Error,
+    },
+    /// A background task panicked; `task_id` is unset outside of tasks.
+    TaskPanicked {
+        task_id: Option<tokio::task::Id>,
+        message: String,
+    },
 }
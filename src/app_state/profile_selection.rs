@@ -1,10 +1,17 @@
+use crate::app_state::FocusedPanel;
 use crate::toml_parser::Profile;
+use crate::utils::file_utils::{load_function_history, FunctionHistory};
 use ratatui::widgets::ListState;
 
 #[derive(Debug)]
 pub struct ProfileSelection {
     pub list_state: ListState,
     pub profiles: Vec<Profile>,
+    /// Favorite and recently used functions of the highlighted profile.
+    pub history: FunctionHistory,
+    pub pinned_state: ListState,
+    /// `Right` while picking one of the pinned functions.
+    pub focused_panel: FocusedPanel,
 }
 
 impl ProfileSelection {
@@ -14,25 +21,95 @@ impl ProfileSelection {
             list_state.select(Some(0));
         }
 
-        Self {
+        let mut selection = Self {
             list_state,
             profiles,
+            history: FunctionHistory::default(),
+            pinned_state: ListState::default(),
+            focused_panel: FocusedPanel::Left,
+        };
+        selection.load_history();
+        selection
+    }
+
+    pub fn load_history(&mut self) {
+        // Only a preview; the function list reports problems with the file
+        self.history = self
+            .selected_profile()
+            .and_then(|profile| load_function_history(&profile.name, &profile.region).ok())
+            .unwrap_or_default();
+    }
+
+    /// Favorites first, then recently used functions that aren't favorites.
+    pub fn pinned_functions(&self) -> Vec<&str> {
+        let recent = self
+            .history
+            .recent
+            .iter()
+            .filter(|name| !self.history.is_favorite(name));
+        self.history
+            .favorites
+            .iter()
+            .chain(recent)
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused_panel = match self.focused_panel {
+            FocusedPanel::Left if !self.history.is_empty() => {
+                self.pinned_state.select(Some(0));
+                FocusedPanel::Right
+            }
+            _ => FocusedPanel::Left,
+        };
+    }
+
+    /// The pinned function picked in the right panel.
+    pub fn selected_function(&self) -> Option<String> {
+        if self.focused_panel != FocusedPanel::Right {
+            return None;
         }
+        let index = self.pinned_state.selected()?;
+        self.pinned_functions()
+            .get(index)
+            .map(|name| name.to_string())
     }
 
     pub fn next(&mut self) {
+        if self.focused_panel == FocusedPanel::Right {
+            let count = self.pinned_functions().len();
+            let current = self.pinned_state.selected().unwrap_or(0);
+            self.pinned_state
+                .select(Some((current + 1).min(count.saturating_sub(1))));
+            return;
+        }
         if !self.profiles.is_empty() {
             let current = self.list_state.selected().unwrap_or(0);
             let next = (current + 1).min(self.profiles.len() - 1);
             self.list_state.select(Some(next));
+            self.load_history();
         }
     }
 
     pub fn previous(&mut self) {
+        if self.focused_panel == FocusedPanel::Right {
+            let current = self.pinned_state.selected().unwrap_or(0);
+            self.pinned_state.select(Some(current.saturating_sub(1)));
+            return;
+        }
         if !self.profiles.is_empty() {
             let current = self.list_state.selected().unwrap_or(0);
             let next = current.saturating_sub(1);
             self.list_state.select(Some(next));
+            self.load_history();
+        }
+    }
+
+    pub fn select_by_name(&mut self, name: &str) {
+        if let Some(index) = self.profiles.iter().position(|p| p.name == name) {
+            self.list_state.select(Some(index));
+            self.load_history();
         }
     }
 
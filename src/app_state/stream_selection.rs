@@ -0,0 +1,112 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::spawn;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::app_state::{loading::LoadingState, AppMessage};
+use crate::backend::{LogBackend, LogSource, LogStreamInfo};
+
+/// How many of the most recently written streams are listed.
+const STREAM_LIST_LIMIT: usize = 200;
+
+/// Lists the streams of one log group. For Lambda each stream is one
+/// execution environment, so this shows how invocations were spread over
+/// containers.
+#[derive(Debug)]
+pub struct StreamSelection {
+    pub source: LogSource,
+    pub streams: Vec<LogStreamInfo>,
+    pub filtered_streams: Vec<LogStreamInfo>,
+    pub selected_index: usize,
+    pub filter_input: String,
+    pub loading: Option<LoadingState>,
+    backend: Arc<dyn LogBackend>,
+}
+
+impl StreamSelection {
+    pub fn new(source: LogSource, backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            source,
+            streams: Vec::new(),
+            filtered_streams: Vec::new(),
+            selected_index: 0,
+            filter_input: String::new(),
+            loading: None,
+            backend,
+        }
+    }
+
+    /// Lists the streams in the background; the result arrives as
+    /// `AppMessage::LogStreamsLoaded`.
+    pub fn start_loading(&mut self, tx: &UnboundedSender<AppMessage>) {
+        let backend = Arc::clone(&self.backend);
+        let log_group_name = self.source.log_group_name.clone();
+        let tx = tx.clone();
+
+        let handle = spawn(async move {
+            let result = backend
+                .describe_log_streams(&log_group_name, STREAM_LIST_LIMIT)
+                .await;
+            let _ = tx.send(AppMessage::LogStreamsLoaded {
+                log_group_name,
+                result,
+            });
+        });
+
+        self.loading = Some(LoadingState::new("Loading log streams", handle));
+    }
+
+    /// Applies a listing; results for another group or a cancelled load are
+    /// ignored.
+    pub fn handle_loaded(
+        &mut self,
+        log_group_name: String,
+        result: Result<Vec<LogStreamInfo>>,
+    ) -> Result<()> {
+        if log_group_name != self.source.log_group_name || self.loading.take().is_none() {
+            return Ok(());
+        }
+
+        self.streams = result?;
+        self.update_filter();
+        Ok(())
+    }
+
+    pub fn update_filter(&mut self) {
+        let selected_name = self.selected().map(|stream| stream.name.clone());
+        let filter_lower = self.filter_input.to_lowercase();
+        let keywords: Vec<&str> = filter_lower.split_whitespace().collect();
+
+        self.filtered_streams = self
+            .streams
+            .iter()
+            .filter(|stream| {
+                let name = stream.name.to_lowercase();
+                keywords.iter().all(|&keyword| name.contains(keyword))
+            })
+            .cloned()
+            .collect();
+
+        self.selected_index = selected_name
+            .and_then(|name| {
+                self.filtered_streams
+                    .iter()
+                    .position(|stream| stream.name == name)
+            })
+            .unwrap_or(0);
+    }
+
+    pub fn selected(&self) -> Option<&LogStreamInfo> {
+        self.filtered_streams.get(self.selected_index)
+    }
+
+    pub fn next(&mut self) {
+        if !self.filtered_streams.is_empty() {
+            self.selected_index = (self.selected_index + 1).min(self.filtered_streams.len() - 1);
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::toml_parser::Profile;
+
+const FALLBACK_REGION: &str = "us-east-1";
+
+/// Discovers profiles from the AWS shared config and credentials files,
+/// honouring `AWS_CONFIG_FILE` and `AWS_SHARED_CREDENTIALS_FILE`.
+///
+/// Profiles without a configured region fall back to `AWS_REGION`, then
+/// `AWS_DEFAULT_REGION`, then `us-east-1`.
+pub fn read_shared_profiles() -> Result<Vec<Profile>> {
+    let mut regions: BTreeMap<String, Option<String>> = BTreeMap::new();
+
+    if let Some(path) = shared_file_path("AWS_CONFIG_FILE", "config") {
+        for (section, values) in read_ini(&path)? {
+            // `[default]` or `[profile name]`; skip `[sso-session ...]`, `[services ...]` etc.
+            let name = match section.split_once(char::is_whitespace) {
+                Some(("profile", name)) => name.trim().to_string(),
+                None => section,
+                Some(_) => continue,
+            };
+            regions.insert(name, values.get("region").cloned());
+        }
+    }
+
+    if let Some(path) = shared_file_path("AWS_SHARED_CREDENTIALS_FILE", "credentials") {
+        for (section, values) in read_ini(&path)? {
+            let region = values.get("region").cloned();
+            let entry = regions.entry(section).or_default();
+            if entry.is_none() {
+                *entry = region;
+            }
+        }
+    }
+
+    let default_region = env::var("AWS_REGION")
+        .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+        .unwrap_or_else(|_| FALLBACK_REGION.to_string());
+
+    let mut profiles: Vec<Profile> = regions
+        .into_iter()
+        .map(|(name, region)| Profile {
+            name,
+            region: region.unwrap_or_else(|| default_region.clone()),
+        })
+        .collect();
+
+    // Keep `default` at the top, the rest alphabetical
+    profiles.sort_by_key(|p| p.name != "default");
+    Ok(profiles)
+}
+
+fn shared_file_path(env_var: &str, file_name: &str) -> Option<PathBuf> {
+    let path = match env::var_os(env_var) {
+        Some(path) => PathBuf::from(path),
+        None => dirs::home_dir()?.join(".aws").join(file_name),
+    };
+    path.exists().then_some(path)
+}
+
+type IniSection = (String, BTreeMap<String, String>);
+
+fn read_ini(path: &PathBuf) -> Result<Vec<IniSection>> {
+    let content = fs::read_to_string(path)?;
+    Ok(parse_ini(&content))
+}
+
+fn parse_ini(content: &str) -> Vec<IniSection> {
+    let mut sections: Vec<IniSection> = Vec::new();
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        // Indented lines are nested settings (e.g. under `s3 =`), which we never need
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with(';')
+            || raw_line.starts_with(char::is_whitespace)
+        {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((section.trim().to_string(), BTreeMap::new()));
+        } else if let (Some((key, value)), Some((_, values))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            values
+                .entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# managed by sso setup
+[default]
+region = eu-west-1
+
+[profile staging]
+; no region here
+output = json
+s3 =
+  max_concurrent_requests = 20
+
+[profile  prod ]
+region=eu-central-1
+region = us-west-2
+
+[sso-session corp]
+sso_region = eu-west-1
+";
+
+    const CREDENTIALS: &str = "\
+[staging]
+aws_access_key_id = AKIAEXAMPLE
+region = ap-southeast-2
+
+[prod]
+region = us-east-2
+
+[ci]
+aws_access_key_id = AKIAEXAMPLE
+";
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_ini_sections() {
+        assert_eq!(
+            parse_ini(CONFIG),
+            vec![
+                ("default".to_string(), values(&[("region", "eu-west-1")])),
+                (
+                    "profile staging".to_string(),
+                    values(&[("output", "json"), ("s3", "")])
+                ),
+                (
+                    "profile  prod".to_string(),
+                    values(&[("region", "eu-central-1")])
+                ),
+                (
+                    "sso-session corp".to_string(),
+                    values(&[("sso_region", "eu-west-1")])
+                ),
+            ]
+        );
+        // Settings before any section have nowhere to go
+        assert_eq!(parse_ini("region = eu-west-1\n"), vec![]);
+    }
+
+    // The only test touching these variables, so setting them doesn't race
+    #[test]
+    fn reads_profiles_from_the_shared_files() {
+        let dir = env::temp_dir().join(format!("aws-logs-tui-profiles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (config, credentials) = (dir.join("config"), dir.join("credentials"));
+        fs::write(&config, CONFIG).unwrap();
+        fs::write(&credentials, CREDENTIALS).unwrap();
+        env::set_var("AWS_CONFIG_FILE", &config);
+        env::set_var("AWS_SHARED_CREDENTIALS_FILE", &credentials);
+        env::set_var("AWS_REGION", "sa-east-1");
+
+        let profiles: Vec<(String, String)> = read_shared_profiles()
+            .unwrap()
+            .into_iter()
+            .map(|profile| (profile.name, profile.region))
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected = [
+            ("default", "eu-west-1"),
+            // Only credentials, so the region comes from the environment
+            ("ci", "sa-east-1"),
+            // The config file's region wins over the credentials file's
+            ("prod", "eu-central-1"),
+            ("staging", "ap-southeast-2"),
+        ];
+        assert_eq!(
+            profiles,
+            expected.map(|(name, region)| (name.to_string(), region.to_string()))
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_cloudwatchlogs::types::{OrderBy, QueryStatus as SdkQueryStatus};
+use aws_sdk_cloudwatchlogs::Client as CloudWatchLogsClient;
+use aws_sdk_lambda::Client as LambdaClient;
+use chrono::DateTime;
+use std::collections::BTreeMap;
+
+use super::{
+    FunctionCatalog, FunctionInfo, InsightsQuery, LogBackend, LogEvent, LogGroupInfo, LogPage,
+    LogQuery, LogStreamInfo, QueryResults, QueryStatistics, QueryStatus, StreamPage,
+};
+use crate::error::AwsError;
+use crate::toml_parser::Profile;
+
+#[derive(Debug, Clone)]
+pub struct AwsBackend {
+    logs_client: CloudWatchLogsClient,
+    lambda_client: LambdaClient,
+}
+
+impl AwsBackend {
+    pub async fn new(profile: &Profile) -> Self {
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .profile_name(&profile.name)
+            .region(Region::new(profile.region.clone()))
+            .load()
+            .await;
+
+        Self {
+            logs_client: CloudWatchLogsClient::new(&config),
+            lambda_client: LambdaClient::new(&config),
+        }
+    }
+}
+
+#[async_trait]
+impl LogBackend for AwsBackend {
+    async fn filter_log_events(
+        &self,
+        query: &LogQuery,
+        next_token: Option<String>,
+    ) -> Result<LogPage> {
+        let response = self
+            .logs_client
+            .filter_log_events()
+            .log_group_name(&query.log_group_name)
+            .start_time(query.start_time)
+            .end_time(query.end_time)
+            .set_filter_pattern(query.filter_pattern.clone())
+            .limit(100)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+
+        let events = response
+            .events
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| LogEvent {
+                event_id: e.event_id,
+                log_stream_name: e.log_stream_name,
+                timestamp: e.timestamp.unwrap_or(0),
+                ingestion_time: e.ingestion_time.unwrap_or(0),
+                message: e.message.unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(LogPage {
+            events,
+            next_token: response.next_token,
+        })
+    }
+
+    async fn start_query(&self, query: &InsightsQuery) -> Result<String> {
+        // Insights takes the range in seconds
+        let response = self
+            .logs_client
+            .start_query()
+            .log_group_name(&query.log_group_name)
+            .start_time(query.start_time / 1000)
+            .end_time(query.end_time / 1000)
+            .query_string(&query.query_string)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+
+        response
+            .query_id
+            .ok_or_else(|| anyhow::anyhow!("StartQuery returned no query ID"))
+    }
+
+    async fn get_query_results(&self, query_id: &str) -> Result<QueryResults> {
+        let response = self
+            .logs_client
+            .get_query_results()
+            .query_id(query_id)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+
+        let status = match response.status {
+            Some(SdkQueryStatus::Scheduled) => QueryStatus::Scheduled,
+            Some(SdkQueryStatus::Running) => QueryStatus::Running,
+            Some(SdkQueryStatus::Complete) => QueryStatus::Complete,
+            Some(SdkQueryStatus::Failed) => QueryStatus::Failed,
+            Some(SdkQueryStatus::Cancelled) => QueryStatus::Cancelled,
+            Some(SdkQueryStatus::Timeout) => QueryStatus::Timeout,
+            _ => QueryStatus::Unknown,
+        };
+
+        let rows = response
+            .results
+            .unwrap_or_default()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .filter_map(|field| Some((field.field?, field.value.unwrap_or_default())))
+                    .collect()
+            })
+            .collect();
+
+        let statistics = response
+            .statistics
+            .map(|s| QueryStatistics {
+                records_matched: s.records_matched,
+                records_scanned: s.records_scanned,
+                bytes_scanned: s.bytes_scanned,
+            })
+            .unwrap_or_default();
+
+        Ok(QueryResults {
+            status,
+            rows,
+            statistics,
+        })
+    }
+
+    async fn stop_query(&self, query_id: &str) -> Result<()> {
+        self.logs_client
+            .stop_query()
+            .query_id(query_id)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+        Ok(())
+    }
+
+    async fn describe_log_groups(&self, prefix: Option<&str>) -> Result<Vec<LogGroupInfo>> {
+        let mut log_groups = Vec::new();
+        let mut next_token = None;
+
+        loop {
+            let response = self
+                .logs_client
+                .describe_log_groups()
+                .set_log_group_name_prefix(prefix.map(String::from))
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(AwsError::from_sdk)?;
+
+            for group in response.log_groups() {
+                if let Some(name) = &group.log_group_name {
+                    log_groups.push(LogGroupInfo {
+                        name: name.clone(),
+                        stored_bytes: group.stored_bytes,
+                        retention_days: group.retention_in_days,
+                        creation_time: group.creation_time,
+                    });
+                }
+            }
+
+            next_token = response.next_token().map(String::from);
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(log_groups)
+    }
+
+    async fn describe_log_streams(
+        &self,
+        log_group_name: &str,
+        limit: usize,
+    ) -> Result<Vec<LogStreamInfo>> {
+        let mut streams = Vec::new();
+        let mut next_token = None;
+
+        loop {
+            let response = self
+                .logs_client
+                .describe_log_streams()
+                .log_group_name(log_group_name)
+                .order_by(OrderBy::LastEventTime)
+                .descending(true)
+                .limit((limit - streams.len()).min(50) as i32)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(AwsError::from_sdk)?;
+
+            for stream in response.log_streams() {
+                if let Some(name) = &stream.log_stream_name {
+                    streams.push(LogStreamInfo {
+                        name: name.clone(),
+                        first_event_time: stream.first_event_timestamp,
+                        last_event_time: stream.last_event_timestamp,
+                    });
+                }
+            }
+
+            next_token = response.next_token().map(String::from);
+            if next_token.is_none() || streams.len() >= limit {
+                break;
+            }
+        }
+
+        Ok(streams)
+    }
+
+    async fn get_log_events(
+        &self,
+        log_group_name: &str,
+        log_stream_name: &str,
+        next_token: Option<String>,
+        start_from_head: bool,
+    ) -> Result<StreamPage> {
+        let response = self
+            .logs_client
+            .get_log_events()
+            .log_group_name(log_group_name)
+            .log_stream_name(log_stream_name)
+            .start_from_head(start_from_head)
+            .limit(100)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+
+        let events = response
+            .events
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| LogEvent {
+                event_id: None,
+                log_stream_name: Some(log_stream_name.to_string()),
+                timestamp: e.timestamp.unwrap_or(0),
+                ingestion_time: e.ingestion_time.unwrap_or(0),
+                message: e.message.unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(StreamPage {
+            events,
+            next_forward_token: response.next_forward_token,
+            next_backward_token: response.next_backward_token,
+        })
+    }
+}
+
+#[async_trait]
+impl FunctionCatalog for AwsBackend {
+    async fn list_functions(&self) -> Result<Vec<FunctionInfo>> {
+        let mut functions = Vec::new();
+        let mut next_marker = None;
+
+        loop {
+            let response = self
+                .lambda_client
+                .list_functions()
+                .set_marker(next_marker)
+                .send()
+                .await
+                .map_err(AwsError::from_sdk)?;
+
+            for function in response.functions() {
+                if let Some(name) = &function.function_name {
+                    functions.push(FunctionInfo {
+                        name: name.clone(),
+                        arn: function.function_arn.clone(),
+                        runtime: function
+                            .runtime()
+                            .map(|runtime| runtime.as_str().to_string()),
+                        memory_mb: function.memory_size,
+                        timeout_secs: function.timeout,
+                        handler: function.handler.clone(),
+                        last_modified: function.last_modified().and_then(parse_last_modified),
+                        code_size: Some(function.code_size),
+                        architectures: function
+                            .architectures()
+                            .iter()
+                            .map(|architecture| architecture.as_str().to_string())
+                            .collect(),
+                        description: function.description.clone().filter(|d| !d.is_empty()),
+                    })
+                }
+            }
+
+            next_marker = response.next_marker().map(String::from);
+            if next_marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(functions)
+    }
+
+    async fn list_tags(&self, function: &FunctionInfo) -> Result<BTreeMap<String, String>> {
+        let Some(arn) = &function.arn else {
+            return Ok(BTreeMap::new());
+        };
+
+        let response = self
+            .lambda_client
+            .list_tags()
+            .resource(arn)
+            .send()
+            .await
+            .map_err(AwsError::from_sdk)?;
+
+        Ok(response.tags.unwrap_or_default().into_iter().collect())
+    }
+}
+
+/// Lambda reports e.g. `2024-03-01T12:34:56.789+0000`.
+fn parse_last_modified(value: &str) -> Option<i64> {
+    DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .ok()
+        .map(|time| time.timestamp_millis())
+}
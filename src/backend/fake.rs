@@ -0,0 +1,608 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Local};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration as StdDuration;
+
+use super::fake_insights::run_query;
+use super::filter_pattern::FilterPattern;
+use super::{
+    lambda_log_group, FunctionCatalog, FunctionInfo, InsightsQuery, LogBackend, LogEvent,
+    LogGroupInfo, LogPage, LogQuery, LogStreamInfo, QueryResults, QueryStatistics, QueryStatus,
+    StreamPage,
+};
+use crate::error::AwsError;
+
+const PAGE_SIZE: usize = 100;
+
+/// An in-memory backend for tests and the `--fake` demo mode.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    functions: Vec<FunctionInfo>,
+    tags: HashMap<String, BTreeMap<String, String>>,
+    log_groups: HashMap<String, Vec<LogEvent>>,
+    latency: StdDuration,
+    queries: Mutex<HashMap<String, FakeQuery>>,
+    next_query_id: AtomicU64,
+    filter_requests: AtomicU64,
+}
+
+/// A started Insights query; it reports `Running` on the first poll.
+#[derive(Debug)]
+struct FakeQuery {
+    polls: usize,
+    results: Result<QueryResults, String>,
+    stopped: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    #[serde(default)]
+    functions: Vec<FixtureFunction>,
+    /// Tags by function name.
+    #[serde(default)]
+    tags: HashMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    log_groups: HashMap<String, Vec<LogEvent>>,
+}
+
+/// Fixtures list functions by name, or with their metadata.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FixtureFunction {
+    Name(String),
+    Info(FunctionInfo),
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_function(mut self, function: FunctionInfo) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    pub fn with_tags(mut self, function_name: &str, tags: &[(&str, &str)]) -> Self {
+        self.tags.insert(
+            function_name.to_string(),
+            tags.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Delays every request, so loading states can be seen in the demo.
+    pub fn with_latency(mut self, latency: StdDuration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_events(mut self, log_group_name: &str, events: Vec<LogEvent>) -> Self {
+        let group = self
+            .log_groups
+            .entry(log_group_name.to_string())
+            .or_default();
+        group.extend(events);
+        group.sort_by_key(|e| e.timestamp);
+        self
+    }
+
+    /// Loads functions and log groups from a JSON fixture of the form
+    /// `{ "functions": [<name or FunctionInfo>...], "tags": { "<function>": {...} },
+    /// "log_groups": { "<group>": [<LogEvent>...] } }`.
+    pub fn from_fixture(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let fixture: Fixture = serde_json::from_str(&content)?;
+
+        let mut backend = Self::new();
+        backend.functions = fixture
+            .functions
+            .into_iter()
+            .map(|function| match function {
+                FixtureFunction::Name(name) => FunctionInfo::named(&name),
+                FixtureFunction::Info(info) => info,
+            })
+            .collect();
+        backend.tags = fixture.tags;
+        for (group, events) in fixture.log_groups {
+            backend = backend.with_events(&group, events);
+        }
+        Ok(backend)
+    }
+
+    /// How many `FilterLogEvents` pages were requested.
+    #[cfg(test)]
+    pub fn filter_requests(&self) -> u64 {
+        self.filter_requests.load(Ordering::Relaxed)
+    }
+
+    fn group_events(&self, log_group_name: &str) -> Result<&Vec<LogEvent>> {
+        self.log_groups.get(log_group_name).ok_or_else(|| {
+            AwsError::new(
+                Some("ResourceNotFoundException"),
+                &format!("The specified log group does not exist: {}", log_group_name),
+            )
+            .into()
+        })
+    }
+
+    /// Generates a few days of Lambda-style logs for a handful of functions.
+    pub fn demo() -> Self {
+        // (name, runtime, memory, timeout, architecture, description)
+        let functions = [
+            (
+                "orders-prod-processor",
+                "nodejs20.x",
+                256,
+                30,
+                "arm64",
+                "Processes new orders from the queue",
+            ),
+            (
+                "orders-staging-processor",
+                "nodejs20.x",
+                256,
+                30,
+                "arm64",
+                "Processes new orders from the queue",
+            ),
+            (
+                "payments-prod-api",
+                "java17",
+                1024,
+                15,
+                "x86_64",
+                "Payments REST API",
+            ),
+            ("payments-prod-webhook", "python3.9", 128, 3, "x86_64", ""),
+            (
+                "users-prod-auth",
+                "python3.12",
+                512,
+                10,
+                "arm64",
+                "Token issuing and validation",
+            ),
+            ("users-dev-auth", "python3.12", 512, 10, "arm64", ""),
+        ];
+
+        let now = Local::now().timestamp_millis();
+        let start = now - Duration::days(3).num_milliseconds();
+        let mut seed: u64 = 0x5eed;
+        let mut next_random = move || {
+            // Small LCG so the demo data is stable between runs
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (seed >> 33) as i64
+        };
+
+        let mut backend = Self::new().with_latency(StdDuration::from_millis(100));
+        let mut event_counter = 0u64;
+        for (name, runtime, memory_mb, timeout_secs, architecture, description) in functions {
+            let mut events = Vec::new();
+            let mut timestamp = start + next_random() % 60_000;
+            let mut invocation = 0;
+            let mut stream = String::new();
+            let mut cold_start = true;
+
+            while timestamp < now {
+                let request_id = format!(
+                    "{:08x}-{:04x}-4{:03x}-a{:03x}-{:012x}",
+                    next_random() & 0xffff_ffff,
+                    next_random() & 0xffff,
+                    next_random() & 0xfff,
+                    next_random() & 0xfff,
+                    next_random() & 0xffff_ffff_ffff,
+                );
+                // Each execution environment serves a run of invocations
+                if cold_start {
+                    stream = format!(
+                        "{}/[$LATEST]{:08x}{:08x}{:08x}{:08x}",
+                        DateTime::from_timestamp_millis(timestamp)
+                            .unwrap_or_default()
+                            .format("%Y/%m/%d"),
+                        next_random(),
+                        next_random(),
+                        next_random(),
+                        next_random()
+                    );
+                }
+                let timed_out = next_random() % 60 == 0;
+                let out_of_memory = !timed_out && next_random() % 150 == 0;
+                let duration = if timed_out {
+                    timeout_secs as i64 * 1000
+                } else {
+                    20 + next_random() % 900
+                };
+
+                let mut lines = Vec::new();
+                if cold_start {
+                    lines.push(format!(
+                        "INIT_START Runtime Version: {}.v13\tRuntime Version ARN: arn:aws:lambda:us-east-1::runtime:0f1e2d3c",
+                        runtime
+                    ));
+                }
+                lines.push(format!("START RequestId: {} Version: $LATEST", request_id));
+                lines.push(format!(
+                    "{{\"level\":\"INFO\",\"message\":\"processing request\",\"function\":\"{}\",\"requestId\":\"{}\"}}",
+                    name, request_id
+                ));
+                match next_random() % 20 {
+                    _ if out_of_memory => lines.push(format!(
+                        "RequestId: {} Error: Runtime exited with error: signal: killed\nRuntime.ExitError",
+                        request_id
+                    )),
+                    _ if timed_out => lines.push(format!(
+                        "{} {} Task timed out after {}.00 seconds",
+                        DateTime::from_timestamp_millis(timestamp + duration)
+                            .unwrap_or_default()
+                            .format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                        request_id,
+                        timeout_secs
+                    )),
+                    0 => lines.push(format!(
+                        "{}\tERROR\tunhandled exception: connection reset by peer",
+                        request_id
+                    )),
+                    1 | 2 => lines.push(format!(
+                        "{}\tWARN\tretrying downstream call (attempt 2)",
+                        request_id
+                    )),
+                    _ => {}
+                }
+                lines.push(format!("END RequestId: {}", request_id));
+                let mut report = format!(
+                    "REPORT RequestId: {}\tDuration: {}.{:02} ms\tBilled Duration: {} ms\tMemory Size: {} MB\tMax Memory Used: {} MB",
+                    request_id,
+                    duration,
+                    next_random() % 100,
+                    duration + 1,
+                    memory_mb,
+                    if out_of_memory {
+                        memory_mb as i64
+                    } else {
+                        (60 + next_random() % 120).min(memory_mb as i64)
+                    }
+                );
+                if cold_start {
+                    report.push_str(&format!(
+                        "\tInit Duration: {}.{:02} ms",
+                        150 + next_random() % 300,
+                        next_random() % 100
+                    ));
+                }
+                if timed_out {
+                    report.push_str("\tStatus: timeout");
+                } else if out_of_memory {
+                    report.push_str("\tStatus: error\tError Type: Runtime.OutOfMemory");
+                }
+                lines.push(report);
+
+                for (offset, message) in lines.into_iter().enumerate() {
+                    event_counter += 1;
+                    let event_time = timestamp + offset as i64 * (duration / 4).max(1);
+                    events.push(LogEvent {
+                        event_id: Some(format!("{:056}", event_counter)),
+                        log_stream_name: Some(stream.clone()),
+                        timestamp: event_time,
+                        ingestion_time: event_time + 150,
+                        message,
+                    });
+                }
+
+                invocation += 1;
+                // A crashed environment is replaced by a fresh one
+                cold_start = out_of_memory || invocation % 50 == 0;
+                timestamp += 60_000 + next_random() % 600_000;
+            }
+
+            let handler = match runtime {
+                runtime if runtime.starts_with("java") => "com.example.Handler::handleRequest",
+                runtime if runtime.starts_with("python") => "app.handler",
+                _ => "index.handler",
+            };
+            backend = backend
+                .with_function(FunctionInfo {
+                    name: name.to_string(),
+                    arn: Some(format!(
+                        "arn:aws:lambda:us-east-1:123456789012:function:{}",
+                        name
+                    )),
+                    runtime: Some(runtime.to_string()),
+                    memory_mb: Some(memory_mb),
+                    timeout_secs: Some(timeout_secs),
+                    handler: Some(handler.to_string()),
+                    last_modified: Some(
+                        now - Duration::days(next_random() % 400).num_milliseconds(),
+                    ),
+                    code_size: Some(40_000 + next_random() % 20_000_000),
+                    architectures: vec![architecture.to_string()],
+                    description: Some(description.to_string()).filter(|d| !d.is_empty()),
+                })
+                .with_events(&lambda_log_group(name), events);
+            // Names follow `<service>-<stage>-<handler>`
+            let mut parts = name.split('-');
+            if let (Some(service), Some(stage)) = (parts.next(), parts.next()) {
+                backend = backend.with_tags(name, &[("service", service), ("stage", stage)]);
+            }
+        }
+
+        // A few groups that don't belong to a Lambda function
+        let other_groups = [
+            ("/ecs/orders-api", "ecs/orders-api/3f9c1d2e"),
+            ("/aws/codebuild/orders-build", "4b7e9a10-build"),
+            (
+                "API-Gateway-Execution-Logs_a1b2c3d4e5/prod",
+                "8f14e45fceea167a",
+            ),
+        ];
+        for (group, stream) in other_groups {
+            let mut events = Vec::new();
+            let mut timestamp = start + next_random() % 60_000;
+            while timestamp < now {
+                event_counter += 1;
+                let status = match next_random() % 25 {
+                    0 => 500,
+                    1 | 2 => 404,
+                    _ => 200,
+                };
+                events.push(LogEvent {
+                    event_id: Some(format!("{:056}", event_counter)),
+                    log_stream_name: Some(stream.to_string()),
+                    timestamp,
+                    ingestion_time: timestamp + 150,
+                    message: format!(
+                        "10.0.{}.{} \"GET /orders/{} HTTP/1.1\" {} {} {}ms",
+                        next_random() % 4,
+                        next_random() % 255,
+                        next_random() % 10_000,
+                        status,
+                        200 + next_random() % 4000,
+                        5 + next_random() % 300
+                    ),
+                });
+                timestamp += 30_000 + next_random() % 300_000;
+            }
+            backend = backend.with_events(group, events);
+        }
+
+        backend
+    }
+}
+
+#[async_trait]
+impl LogBackend for FakeBackend {
+    async fn filter_log_events(
+        &self,
+        query: &LogQuery,
+        next_token: Option<String>,
+    ) -> Result<LogPage> {
+        tokio::time::sleep(self.latency).await;
+        self.filter_requests.fetch_add(1, Ordering::Relaxed);
+
+        let events = self.group_events(&query.log_group_name)?;
+
+        let offset = match next_token {
+            Some(token) => token.parse::<usize>()?,
+            None => 0,
+        };
+
+        let pattern = query.filter_pattern.as_deref().map(FilterPattern::parse);
+        let matching: Vec<&LogEvent> = events
+            .iter()
+            .filter(|e| e.timestamp >= query.start_time && e.timestamp <= query.end_time)
+            .filter(|e| pattern.as_ref().is_none_or(|p| p.matches(&e.message)))
+            .collect();
+
+        let page: Vec<LogEvent> = matching
+            .iter()
+            .skip(offset)
+            .take(PAGE_SIZE)
+            .map(|e| (*e).clone())
+            .collect();
+
+        let next_offset = offset + page.len();
+        Ok(LogPage {
+            events: page,
+            next_token: (next_offset < matching.len()).then(|| next_offset.to_string()),
+        })
+    }
+
+    async fn start_query(&self, query: &InsightsQuery) -> Result<String> {
+        tokio::time::sleep(self.latency).await;
+
+        let events = self.group_events(&query.log_group_name)?;
+        let in_range: Vec<LogEvent> = events
+            .iter()
+            .filter(|e| e.timestamp >= query.start_time && e.timestamp <= query.end_time)
+            .cloned()
+            .collect();
+
+        let results = run_query(&in_range, &query.query_string)
+            .map(|rows| QueryResults {
+                status: QueryStatus::Complete,
+                statistics: QueryStatistics {
+                    records_matched: rows.len() as f64,
+                    records_scanned: in_range.len() as f64,
+                    bytes_scanned: in_range.iter().map(|e| e.message.len() as f64).sum(),
+                },
+                rows,
+            })
+            .map_err(|e| e.to_string());
+
+        let query_id = format!(
+            "fake-query-{}",
+            self.next_query_id.fetch_add(1, Ordering::Relaxed)
+        );
+        self.queries.lock().unwrap().insert(
+            query_id.clone(),
+            FakeQuery {
+                polls: 0,
+                results,
+                stopped: false,
+            },
+        );
+        Ok(query_id)
+    }
+
+    async fn get_query_results(&self, query_id: &str) -> Result<QueryResults> {
+        tokio::time::sleep(self.latency).await;
+
+        let mut queries = self.queries.lock().unwrap();
+        let query = queries
+            .get_mut(query_id)
+            .ok_or_else(|| AwsError::new(Some("ResourceNotFoundException"), "Unknown query"))?;
+        query.polls += 1;
+
+        match &query.results {
+            _ if query.stopped => Ok(QueryResults {
+                status: QueryStatus::Cancelled,
+                rows: Vec::new(),
+                statistics: QueryStatistics::default(),
+            }),
+            Ok(_) if query.polls == 1 => Ok(QueryResults {
+                status: QueryStatus::Running,
+                rows: Vec::new(),
+                statistics: QueryStatistics::default(),
+            }),
+            Ok(results) => Ok(results.clone()),
+            Err(message) => Err(AwsError::new(Some("MalformedQueryException"), message).into()),
+        }
+    }
+
+    async fn stop_query(&self, query_id: &str) -> Result<()> {
+        tokio::time::sleep(self.latency).await;
+
+        let mut queries = self.queries.lock().unwrap();
+        let query = queries
+            .get_mut(query_id)
+            .ok_or_else(|| AwsError::new(Some("ResourceNotFoundException"), "Unknown query"))?;
+        query.stopped = true;
+        Ok(())
+    }
+
+    async fn describe_log_groups(&self, prefix: Option<&str>) -> Result<Vec<LogGroupInfo>> {
+        tokio::time::sleep(self.latency).await;
+
+        let mut log_groups: Vec<LogGroupInfo> = self
+            .log_groups
+            .iter()
+            .filter(|(name, _)| prefix.is_none_or(|prefix| name.starts_with(prefix)))
+            .map(|(name, events)| LogGroupInfo {
+                name: name.clone(),
+                stored_bytes: Some(events.iter().map(|e| e.message.len() as i64).sum()),
+                retention_days: name.starts_with("/aws/lambda/").then_some(30),
+                creation_time: events.first().map(|e| e.timestamp),
+            })
+            .collect();
+        // Like the API, which lists groups in name order
+        log_groups.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(log_groups)
+    }
+
+    async fn describe_log_streams(
+        &self,
+        log_group_name: &str,
+        limit: usize,
+    ) -> Result<Vec<LogStreamInfo>> {
+        tokio::time::sleep(self.latency).await;
+
+        let mut streams: HashMap<&str, LogStreamInfo> = HashMap::new();
+        for event in self.group_events(log_group_name)? {
+            let name = event.log_stream_name.as_deref().unwrap_or_default();
+            let stream = streams.entry(name).or_insert_with(|| LogStreamInfo {
+                name: name.to_string(),
+                first_event_time: Some(event.timestamp),
+                last_event_time: Some(event.timestamp),
+            });
+            stream.first_event_time = stream.first_event_time.min(Some(event.timestamp));
+            stream.last_event_time = stream.last_event_time.max(Some(event.timestamp));
+        }
+
+        let mut streams: Vec<LogStreamInfo> = streams.into_values().collect();
+        streams.sort_by_key(|stream| std::cmp::Reverse(stream.last_event_time));
+        streams.truncate(limit);
+        Ok(streams)
+    }
+
+    async fn get_log_events(
+        &self,
+        log_group_name: &str,
+        log_stream_name: &str,
+        next_token: Option<String>,
+        start_from_head: bool,
+    ) -> Result<StreamPage> {
+        tokio::time::sleep(self.latency).await;
+
+        let events: Vec<&LogEvent> = self
+            .group_events(log_group_name)?
+            .iter()
+            .filter(|e| e.log_stream_name.as_deref() == Some(log_stream_name))
+            .collect();
+
+        // Tokens are `f/<offset>` to read forward from, or `b/<offset>` to read back from
+        let (start, end) = match next_token.as_deref().and_then(|t| t.split_once('/')) {
+            Some(("f", offset)) => {
+                let start = offset.parse::<usize>()?.min(events.len());
+                (start, (start + PAGE_SIZE).min(events.len()))
+            }
+            Some(("b", offset)) => {
+                let end = offset.parse::<usize>()?.min(events.len());
+                (end.saturating_sub(PAGE_SIZE), end)
+            }
+            Some(_) => return Err(anyhow::anyhow!("Invalid token")),
+            None if start_from_head => (0, PAGE_SIZE.min(events.len())),
+            None => (events.len().saturating_sub(PAGE_SIZE), events.len()),
+        };
+
+        Ok(StreamPage {
+            events: events[start..end].iter().map(|e| (*e).clone()).collect(),
+            next_forward_token: Some(format!("f/{}", end)),
+            next_backward_token: Some(format!("b/{}", start)),
+        })
+    }
+}
+
+#[async_trait]
+impl FunctionCatalog for FakeBackend {
+    async fn list_functions(&self) -> Result<Vec<FunctionInfo>> {
+        tokio::time::sleep(self.latency).await;
+        Ok(self.functions.clone())
+    }
+
+    async fn list_tags(&self, function: &FunctionInfo) -> Result<BTreeMap<String, String>> {
+        tokio::time::sleep(self.latency).await;
+        Ok(self.tags.get(&function.name).cloned().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn demo_streams_are_named_after_their_first_event() {
+        let backend = FakeBackend::demo();
+        for function in backend.list_functions().await.unwrap() {
+            let log_group = lambda_log_group(&function.name);
+            for stream in backend
+                .describe_log_streams(&log_group, 1000)
+                .await
+                .unwrap()
+            {
+                let first = DateTime::from_timestamp_millis(stream.first_event_time.unwrap())
+                    .unwrap()
+                    .format("%Y/%m/%d/")
+                    .to_string();
+                assert!(stream.name.starts_with(&first), "{} {}", stream.name, first);
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use super::LogEvent;
+use crate::utils::time_utils::parse_duration;
+
+const DEFAULT_LIMIT: usize = 1000;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+type Record = BTreeMap<String, String>;
+
+/// Runs a small subset of the Logs Insights query language over in-memory
+/// events: `fields`/`display`, `filter` (`=`, `!=`, `<`, `>`, `like`, `=~`,
+/// joined by `and`), `sort`, `limit` and `stats count(*) [by field|bin(5m)]`.
+pub fn run_query(events: &[LogEvent], query: &str) -> Result<Vec<Vec<(String, String)>>> {
+    let mut records: Vec<Record> = events.iter().map(to_record).collect();
+    let mut fields = vec!["@timestamp".to_string(), "@message".to_string()];
+    let mut limit = DEFAULT_LIMIT;
+
+    // Newest first, as in the console
+    records.sort_by(|a, b| b["@timestamp"].cmp(&a["@timestamp"]));
+
+    for command in query.split('|').map(str::trim).filter(|c| !c.is_empty()) {
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, args)| (name, args.trim()))
+            .unwrap_or((command, ""));
+
+        match name {
+            "fields" | "display" => {
+                fields = args.split(',').map(|f| f.trim().to_string()).collect();
+            }
+            "filter" => {
+                let conditions = args
+                    .split(" and ")
+                    .map(parse_condition)
+                    .collect::<Result<Vec<_>>>()?;
+                records.retain(|record| conditions.iter().all(|c| c.matches(record)));
+            }
+            "sort" => {
+                let mut parts = args.split_whitespace();
+                let field = parts.next().unwrap_or("@timestamp").to_string();
+                let descending = parts.next() == Some("desc");
+                records.sort_by(|a, b| {
+                    let ordering = compare_values(
+                        a.get(&field).map_or("", String::as_str),
+                        b.get(&field).map_or("", String::as_str),
+                    );
+                    if descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                });
+            }
+            "limit" => {
+                limit = args
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid limit: {}", args))?;
+            }
+            "stats" => {
+                let (records_out, fields_out) = run_stats(&records, args)?;
+                records = records_out;
+                fields = fields_out;
+            }
+            other => return Err(anyhow!("The fake backend does not support `{}`", other)),
+        }
+    }
+
+    Ok(records
+        .into_iter()
+        .take(limit)
+        .map(|record| {
+            fields
+                .iter()
+                .filter_map(|field| Some((field.clone(), record.get(field)?.clone())))
+                .collect()
+        })
+        .collect())
+}
+
+fn to_record(event: &LogEvent) -> Record {
+    let mut record = Record::new();
+    if let Ok(Value::Object(json)) = serde_json::from_str::<Value>(&event.message) {
+        for (key, value) in json {
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            record.insert(key, value);
+        }
+    }
+    record.insert("@timestamp".to_string(), format_timestamp(event.timestamp));
+    record.insert(
+        "@ingestionTime".to_string(),
+        format_timestamp(event.ingestion_time),
+    );
+    record.insert("@message".to_string(), event.message.clone());
+    record.insert(
+        "@logStream".to_string(),
+        event.log_stream_name.clone().unwrap_or_default(),
+    );
+    record
+}
+
+fn format_timestamp(millis: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .unwrap_or_default()
+        .format(TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+struct Condition {
+    field: String,
+    operator: String,
+    value: String,
+}
+
+impl Condition {
+    fn matches(&self, record: &Record) -> bool {
+        let Some(actual) = record.get(&self.field) else {
+            return false;
+        };
+        match self.operator.as_str() {
+            // Regexes are approximated by a substring match
+            "like" | "=~" => actual.contains(&self.value),
+            "=" => compare_values(actual, &self.value) == Ordering::Equal,
+            "!=" => compare_values(actual, &self.value) != Ordering::Equal,
+            "<" => compare_values(actual, &self.value) == Ordering::Less,
+            "<=" => compare_values(actual, &self.value) != Ordering::Greater,
+            ">" => compare_values(actual, &self.value) == Ordering::Greater,
+            ">=" => compare_values(actual, &self.value) != Ordering::Less,
+            _ => false,
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Result<Condition> {
+    let mut parts = condition.trim().splitn(3, char::is_whitespace);
+    let (Some(field), Some(operator), Some(value)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(anyhow!("Unsupported filter: {}", condition.trim()));
+    };
+
+    let value = value.trim();
+    let value = ['"', '\'', '/']
+        .iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|v| v.strip_suffix(*quote))
+        })
+        .unwrap_or(value);
+
+    Ok(Condition {
+        field: field.to_string(),
+        operator: operator.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Compares numerically when both values are numbers.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+fn run_stats(records: &[Record], args: &str) -> Result<(Vec<Record>, Vec<String>)> {
+    let (aggregate, group_by) = match args.split_once(" by ") {
+        Some((aggregate, group_by)) => (aggregate.trim(), Some(group_by.trim())),
+        None => (args.trim(), None),
+    };
+
+    let (function, alias) = match aggregate.split_once(" as ") {
+        Some((function, alias)) => (function.trim(), alias.trim().to_string()),
+        None => (aggregate, aggregate.to_string()),
+    };
+    if !function.starts_with("count(") {
+        return Err(anyhow!(
+            "The fake backend only supports count() in stats, not `{}`",
+            function
+        ));
+    }
+
+    let Some(group_by) = group_by else {
+        let mut record = Record::new();
+        record.insert(alias.clone(), records.len().to_string());
+        return Ok((vec![record], vec![alias]));
+    };
+
+    let bin_millis = group_by
+        .strip_prefix("bin(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(|duration| parse_duration(duration).map(|d| d.num_milliseconds()))
+        .transpose()?;
+
+    let mut groups: BTreeMap<String, usize> = BTreeMap::new();
+    for record in records {
+        let key = match bin_millis {
+            Some(bin) => {
+                let timestamp =
+                    NaiveDateTime::parse_from_str(&record["@timestamp"], TIMESTAMP_FORMAT)
+                        .map(|t| t.and_utc().timestamp_millis())
+                        .unwrap_or(0);
+                format_timestamp(timestamp - timestamp.rem_euclid(bin))
+            }
+            None => record.get(group_by).cloned().unwrap_or_default(),
+        };
+        *groups.entry(key).or_default() += 1;
+    }
+
+    let rows = groups
+        .into_iter()
+        .rev()
+        .map(|(key, count)| {
+            let mut record = Record::new();
+            record.insert(group_by.to_string(), key);
+            record.insert(alias.clone(), count.to_string());
+            record
+        })
+        .collect();
+
+    Ok((rows, vec![group_by.to_string(), alias]))
+}
@@ -0,0 +1,298 @@
+use serde_json::Value;
+
+/// A local approximation of CloudWatch filter pattern syntax, so the fake
+/// backend filters the way `FilterLogEvents` does. Supports terms, quoted
+/// phrases, `?optional` terms, `-excluded` terms and JSON patterns such as
+/// `{ $.level = "ERROR" && $.latency > 500 }`.
+#[derive(Debug, Clone)]
+pub enum FilterPattern {
+    Terms {
+        required: Vec<String>,
+        optional: Vec<String>,
+        excluded: Vec<String>,
+    },
+    /// Alternatives joined by `||`, each a list of conditions joined by `&&`.
+    Json(Vec<Vec<JsonCondition>>),
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonCondition {
+    path: Vec<String>,
+    operator: String,
+    value: String,
+}
+
+impl FilterPattern {
+    pub fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim();
+        if let Some(body) = pattern
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            return FilterPattern::Json(
+                body.split("||")
+                    .map(|alternative| {
+                        alternative
+                            .split("&&")
+                            .filter_map(JsonCondition::parse)
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+
+        let mut required = Vec::new();
+        let mut optional = Vec::new();
+        let mut excluded = Vec::new();
+        for token in tokenize(pattern) {
+            if let Some(term) = token.strip_prefix('?') {
+                optional.push(unquote(term));
+            } else if let Some(term) = token.strip_prefix('-').filter(|t| !t.is_empty()) {
+                excluded.push(unquote(term));
+            } else {
+                required.push(unquote(&token));
+            }
+        }
+        FilterPattern::Terms {
+            required,
+            optional,
+            excluded,
+        }
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        match self {
+            FilterPattern::Terms {
+                required,
+                optional,
+                excluded,
+            } => {
+                required.iter().all(|term| message.contains(term.as_str()))
+                    && (optional.is_empty()
+                        || optional.iter().any(|term| message.contains(term.as_str())))
+                    && !excluded.iter().any(|term| message.contains(term.as_str()))
+            }
+            FilterPattern::Json(alternatives) => {
+                let Ok(json) = serde_json::from_str::<Value>(message) else {
+                    return false;
+                };
+                alternatives.iter().any(|conditions| {
+                    !conditions.is_empty() && conditions.iter().all(|c| c.matches(&json))
+                })
+            }
+        }
+    }
+}
+
+impl JsonCondition {
+    fn parse(condition: &str) -> Option<Self> {
+        let condition = condition.trim();
+        let operators = ["!=", "<=", ">=", "=", "<", ">"];
+        let (index, operator) = operators
+            .iter()
+            .filter_map(|op| condition.find(op).map(|i| (i, *op)))
+            .min_by_key(|(i, op)| (*i, std::cmp::Reverse(op.len())))?;
+
+        let path = condition[..index].trim().strip_prefix('$')?;
+        Some(Self {
+            path: path
+                .split('.')
+                .filter(|part| !part.is_empty())
+                .map(String::from)
+                .collect(),
+            operator: operator.to_string(),
+            value: unquote(condition[index + operator.len()..].trim()),
+        })
+    }
+
+    fn matches(&self, json: &Value) -> bool {
+        let Some(field) = self
+            .path
+            .iter()
+            .try_fold(json, |value, key| value.get(key.as_str()))
+        else {
+            return false;
+        };
+
+        match (field, self.value.parse::<f64>()) {
+            (Value::Number(number), Ok(expected)) => {
+                let actual = number.as_f64().unwrap_or(f64::NAN);
+                match self.operator.as_str() {
+                    "=" => actual == expected,
+                    "!=" => actual != expected,
+                    "<" => actual < expected,
+                    "<=" => actual <= expected,
+                    ">" => actual > expected,
+                    ">=" => actual >= expected,
+                    _ => false,
+                }
+            }
+            _ => {
+                let actual = match field {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                match self.operator.as_str() {
+                    "=" => wildcard_match(&self.value, &actual),
+                    "!=" => !wildcard_match(&self.value, &actual),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Splits on whitespace, keeping double quoted phrases together.
+fn tokenize(pattern: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in pattern.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// An unterminated quote runs to the end of the value.
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"') {
+        Some(quoted) => quoted.strip_suffix('"').unwrap_or(quoted).to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// `*` matches any run of characters, as in CloudWatch JSON string values.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let mut rest = value;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            let Some(stripped) = rest.strip_prefix(part) else {
+                return false;
+            };
+            rest = stripped;
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            let Some(index) = rest.find(part) else {
+                return false;
+            };
+            rest = &rest[index + part.len()..];
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{"level":"ERROR","latency":750,"user":{"id":"u-42"},"ok":false}"#;
+
+    #[test]
+    fn matches_terms() {
+        let message = "2026-10-15 ERROR connection reset by peer";
+        let cases = [
+            ("", true),
+            ("ERROR", true),
+            ("ERROR reset", true),
+            ("ERROR timeout", false),
+            ("error", false),
+            ("?timeout ?reset", true),
+            ("?timeout ?refused", false),
+            ("ERROR ?timeout ?reset", true),
+            ("ERROR -reset", false),
+            ("ERROR -timeout", true),
+            // A lone `-` is a term, found in the date
+            ("-", true),
+            ("\"connection reset\"", true),
+            ("\"reset connection\"", false),
+            ("-\"by peer\"", false),
+            ("?\"by peer\" ?timeout", true),
+        ];
+        for (pattern, expected) in cases {
+            assert_eq!(
+                FilterPattern::parse(pattern).matches(message),
+                expected,
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn matches_json_selectors() {
+        let cases = [
+            (r#"{ $.level = "ERROR" }"#, true),
+            (r#"{ $.level = ERROR }"#, true),
+            (r#"{ $.level = "ERR*" }"#, true),
+            (r#"{ $.level = "*RO*" }"#, true),
+            (r#"{ $.level != "ERROR" }"#, false),
+            ("{ $.latency > 500 }", true),
+            ("{ $.latency >= 750 }", true),
+            ("{ $.latency < 750 }", false),
+            ("{ $.latency != 750.0 }", false),
+            (r#"{ $.user.id = "u-42" }"#, true),
+            ("{ $.ok = false }", true),
+            (r#"{ $.missing = "x" }"#, false),
+            (r#"{ $.level = "ERROR" && $.latency > 1000 }"#, false),
+            (r#"{ $.level = "INFO" || $.latency > 500 }"#, true),
+            (r#"{ $.level = "INFO" || $.latency > 1000 }"#, false),
+        ];
+        for (pattern, expected) in cases {
+            assert_eq!(
+                FilterPattern::parse(pattern).matches(JSON),
+                expected,
+                "{}",
+                pattern
+            );
+        }
+        assert!(!FilterPattern::parse(r#"{ $.level = "ERROR" }"#).matches("ERROR not json"));
+    }
+
+    #[test]
+    fn handles_malformed_patterns() {
+        let message = r#"say "hello world" {"level":"ERROR"}"#;
+        // (pattern, message, expected)
+        let cases = [
+            // JSON patterns without a usable condition match nothing
+            ("{}", JSON, false),
+            ("{ $.level }", JSON, false),
+            (r#"{ level = "ERROR" }"#, JSON, false),
+            (r#"{ $.level = "ERROR" || }"#, JSON, true),
+            // Without both braces the pattern is a list of terms
+            (r#"{ $.level = "ERROR""#, JSON, false),
+            ("{", message, true),
+            // An unterminated quote runs to the end of the pattern
+            ("\"hello world", message, true),
+            ("\"world hello", message, false),
+        ];
+        for (pattern, message, expected) in cases {
+            assert_eq!(
+                FilterPattern::parse(pattern).matches(message),
+                expected,
+                "{}",
+                pattern
+            );
+        }
+    }
+}
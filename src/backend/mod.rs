@@ -0,0 +1,337 @@
+pub mod aws;
+pub mod fake;
+mod fake_insights;
+pub mod filter_pattern;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::toml_parser::Profile;
+
+/// A single CloudWatch log event, independent of the SDK types.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogEvent {
+    pub event_id: Option<String>,
+    pub log_stream_name: Option<String>,
+    pub timestamp: i64,
+    pub ingestion_time: i64,
+    pub message: String,
+}
+
+impl LogEvent {
+    /// Identifies an event across overlapping fetches; fixtures may omit the event ID.
+    pub fn dedup_key(&self) -> String {
+        match &self.event_id {
+            Some(id) => id.clone(),
+            None => format!(
+                "{}:{}:{}",
+                self.timestamp,
+                self.log_stream_name.as_deref().unwrap_or(""),
+                self.message
+            ),
+        }
+    }
+}
+
+/// What the viewer shows logs for: a log group, and the Lambda function
+/// writing to it when it was picked from the function list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogSource {
+    pub log_group_name: String,
+    pub function_name: Option<String>,
+}
+
+impl LogSource {
+    pub fn lambda(function_name: &str) -> Self {
+        Self {
+            log_group_name: lambda_log_group(function_name),
+            function_name: Some(function_name.to_string()),
+        }
+    }
+
+    pub fn log_group(log_group_name: &str) -> Self {
+        Self {
+            log_group_name: log_group_name.to_string(),
+            function_name: None,
+        }
+    }
+
+    /// e.g. `Function: orders-processor` or `Log group: /ecs/orders-api`
+    pub fn label(&self) -> String {
+        match &self.function_name {
+            Some(function_name) => format!("Function: {}", function_name),
+            None => format!("Log group: {}", self.log_group_name),
+        }
+    }
+
+    /// A name usable in file names, e.g. for exports.
+    pub fn file_stem(&self) -> String {
+        match &self.function_name {
+            Some(function_name) => function_name.clone(),
+            None => self
+                .log_group_name
+                .trim_matches('/')
+                .replace(['/', ' ', ':'], "_"),
+        }
+    }
+}
+
+/// A log group as listed by `DescribeLogGroups`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogGroupInfo {
+    pub name: String,
+    pub stored_bytes: Option<i64>,
+    /// `None` means events never expire.
+    pub retention_days: Option<i32>,
+    pub creation_time: Option<i64>,
+}
+
+/// A Lambda function as listed by `ListFunctions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionInfo {
+    pub name: String,
+    pub arn: Option<String>,
+    pub runtime: Option<String>,
+    pub memory_mb: Option<i32>,
+    pub timeout_secs: Option<i32>,
+    pub handler: Option<String>,
+    /// Epoch millis of the last code or configuration update.
+    pub last_modified: Option<i64>,
+    pub code_size: Option<i64>,
+    pub architectures: Vec<String>,
+    pub description: Option<String>,
+}
+
+impl FunctionInfo {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// e.g. `arm64`, or `-` when unknown.
+    pub fn architecture(&self) -> String {
+        if self.architectures.is_empty() {
+            "-".to_string()
+        } else {
+            self.architectures.join(",")
+        }
+    }
+}
+
+/// A log stream as listed by `DescribeLogStreams`; for Lambda, one
+/// execution environment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogStreamInfo {
+    pub name: String,
+    pub first_event_time: Option<i64>,
+    pub last_event_time: Option<i64>,
+}
+
+/// One page of `GetLogEvents`. Paging in a direction has reached the end of
+/// the stream once it returns the token it was given.
+#[derive(Debug, Default)]
+pub struct StreamPage {
+    pub events: Vec<LogEvent>,
+    pub next_forward_token: Option<String>,
+    pub next_backward_token: Option<String>,
+}
+
+/// Parameters for a `FilterLogEvents` sweep over one log group.
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    pub log_group_name: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// A CloudWatch filter pattern, applied server side.
+    pub filter_pattern: Option<String>,
+}
+
+/// One page of results; `next_token` is `None` once the range is exhausted.
+#[derive(Debug, Default)]
+pub struct LogPage {
+    pub events: Vec<LogEvent>,
+    pub next_token: Option<String>,
+}
+
+/// A Logs Insights query over one log group.
+#[derive(Debug, Clone, Default)]
+pub struct InsightsQuery {
+    pub log_group_name: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub query_string: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueryStatus {
+    Scheduled,
+    Running,
+    Complete,
+    Failed,
+    Cancelled,
+    Timeout,
+    Unknown,
+}
+
+impl QueryStatus {
+    /// The query will not produce more results.
+    pub fn is_finished(self) -> bool {
+        !matches!(self, QueryStatus::Scheduled | QueryStatus::Running)
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            QueryStatus::Scheduled => "Scheduled",
+            QueryStatus::Running => "Running",
+            QueryStatus::Complete => "Complete",
+            QueryStatus::Failed => "Failed",
+            QueryStatus::Cancelled => "Cancelled",
+            QueryStatus::Timeout => "Timed out",
+            QueryStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryStatistics {
+    pub records_matched: f64,
+    pub records_scanned: f64,
+    pub bytes_scanned: f64,
+}
+
+/// The results so far of a Logs Insights query; each row is a list of
+/// `(field, value)` pairs.
+#[derive(Debug, Clone)]
+pub struct QueryResults {
+    pub status: QueryStatus,
+    pub rows: Vec<Vec<(String, String)>>,
+    pub statistics: QueryStatistics,
+}
+
+#[async_trait]
+pub trait LogBackend: Send + Sync + Debug {
+    async fn filter_log_events(
+        &self,
+        query: &LogQuery,
+        next_token: Option<String>,
+    ) -> Result<LogPage>;
+
+    /// Starts a Logs Insights query and returns its ID.
+    async fn start_query(&self, query: &InsightsQuery) -> Result<String>;
+
+    async fn get_query_results(&self, query_id: &str) -> Result<QueryResults>;
+
+    /// Stops a running Logs Insights query, which keeps scanning otherwise.
+    async fn stop_query(&self, query_id: &str) -> Result<()>;
+
+    /// Lists every log group, or only those whose name starts with `prefix`.
+    async fn describe_log_groups(&self, prefix: Option<&str>) -> Result<Vec<LogGroupInfo>>;
+
+    /// Lists up to `limit` streams of the group, most recent event first.
+    async fn describe_log_streams(
+        &self,
+        log_group_name: &str,
+        limit: usize,
+    ) -> Result<Vec<LogStreamInfo>>;
+
+    /// Reads one stream in order; without a token it starts at the head or
+    /// the tail of the stream.
+    async fn get_log_events(
+        &self,
+        log_group_name: &str,
+        log_stream_name: &str,
+        next_token: Option<String>,
+        start_from_head: bool,
+    ) -> Result<StreamPage>;
+}
+
+#[async_trait]
+pub trait FunctionCatalog: Send + Sync + Debug {
+    /// Returns every Lambda function visible to the profile.
+    async fn list_functions(&self) -> Result<Vec<FunctionInfo>>;
+
+    /// Returns the tags of one function.
+    async fn list_tags(&self, function: &FunctionInfo) -> Result<BTreeMap<String, String>>;
+}
+
+/// The set of services the app state talks to for one profile.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    pub logs: Arc<dyn LogBackend>,
+    pub functions: Arc<dyn FunctionCatalog>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendKind {
+    Aws,
+    /// In-memory data, either generated demo data or loaded from a JSON fixture.
+    Fake(Option<PathBuf>),
+}
+
+impl BackendKind {
+    pub async fn connect(&self, profile: &Profile) -> Result<Backend> {
+        match self {
+            BackendKind::Aws => {
+                let backend = Arc::new(aws::AwsBackend::new(profile).await);
+                Ok(Backend {
+                    logs: backend.clone(),
+                    functions: backend,
+                })
+            }
+            BackendKind::Fake(fixture) => {
+                let backend = Arc::new(match fixture {
+                    Some(path) => fake::FakeBackend::from_fixture(path)?,
+                    None => fake::FakeBackend::demo(),
+                });
+                Ok(Backend {
+                    logs: backend.clone(),
+                    functions: backend,
+                })
+            }
+        }
+    }
+
+    /// Profiles to offer; the fake backend has a single demo profile.
+    pub fn profiles(&self) -> Result<Vec<Profile>> {
+        match self {
+            BackendKind::Aws => Ok(Config::new()?.aws_profiles),
+            BackendKind::Fake(_) => Ok(vec![Profile {
+                name: "fake".to_string(),
+                region: "local".to_string(),
+            }]),
+        }
+    }
+}
+
+/// Fetches every page of `query`.
+pub async fn fetch_range(backend: &dyn LogBackend, query: &LogQuery) -> Result<Vec<LogEvent>> {
+    let mut logs = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let page = backend.filter_log_events(query, next_token.take()).await?;
+        logs.extend(page.events);
+
+        next_token = page.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(logs)
+}
+
+pub fn lambda_log_group(function_name: &str) -> String {
+    format!("/aws/lambda/{}", function_name)
+}
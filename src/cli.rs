@@ -0,0 +1,103 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::backend::BackendKind;
+use crate::export::ExportFormat;
+use crate::utils::time_utils::DisplayTimezone;
+
+/// Browse, filter and dump AWS Lambda CloudWatch logs from the terminal.
+#[derive(Debug, Parser)]
+#[command(name = "aws-logs-tui", version, about)]
+pub struct Cli {
+    /// AWS profile to use (defaults to the first configured profile)
+    #[arg(long, global = true, env = "AWS_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Override the region of the selected profile
+    #[arg(long, global = true)]
+    pub region: Option<String>,
+
+    /// Use in-memory demo data, or the given JSON fixture, instead of AWS
+    #[arg(long, global = true, value_name = "FIXTURE", num_args = 0..=1, require_equals = true)]
+    pub fake: Option<Option<PathBuf>>,
+
+    /// Show and read times in this timezone: `local`, `utc` or a name like `Europe/Berlin`
+    /// (defaults to `[display] timezone` of the config)
+    #[arg(long, global = true, value_parser = DisplayTimezone::parse)]
+    pub timezone: Option<DisplayTimezone>,
+
+    /// Delete the cached function and log group lists before starting
+    #[arg(long, global = true)]
+    pub clear_cache: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the interactive terminal UI (default)
+    Tui,
+    /// List the Lambda functions of a profile
+    Functions,
+    /// Print the log events of a Lambda function to stdout
+    Logs(Box<LogsArgs>),
+}
+
+#[derive(Debug, Args)]
+pub struct LogsArgs {
+    /// Lambda function name
+    #[arg(required_unless_present = "log_group")]
+    pub function: Option<String>,
+
+    /// Read any log group instead of a Lambda function's, e.g. `/ecs/orders-api`
+    #[arg(long, conflicts_with = "function")]
+    pub log_group: Option<String>,
+
+    /// Start of the range: `now`, a duration ago (`15m`, `2h ago`), `yesterday 09:00`, an
+    /// ISO-8601 timestamp or epoch millis, optionally shifted with `+30m` or `-1h`
+    #[arg(long, default_value = "1h")]
+    pub since: String,
+
+    /// End of the range, in the same format as `--since`
+    #[arg(long, default_value = "now")]
+    pub until: String,
+
+    /// The whole range as one expression, e.g. `2h ago..now`, `yesterday 09:00..10:30` or
+    /// `2026-10-15T14:00Z+30m`
+    #[arg(long, conflicts_with_all = ["since", "until"])]
+    pub range: Option<String>,
+
+    /// Print the latest N events however old they are, instead of a range
+    #[arg(long, value_name = "N", conflicts_with_all = ["since", "until", "range"])]
+    pub recent: Option<usize>,
+
+    /// Print the events of the latest N Lambda invocations, instead of a range
+    #[arg(long, value_name = "N", conflicts_with_all = ["since", "until", "range", "recent"])]
+    pub recent_invocations: Option<usize>,
+
+    /// Only print events containing all of these whitespace separated keywords
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// CloudWatch filter pattern applied server side, e.g. `ERROR -timeout` or `{ $.level = "ERROR" }`
+    #[arg(long)]
+    pub filter_pattern: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Logfile)]
+    pub format: ExportFormat,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl Cli {
+    pub fn backend_kind(&self) -> BackendKind {
+        match &self.fake {
+            Some(fixture) => BackendKind::Fake(fixture.clone()),
+            None => BackendKind::Aws,
+        }
+    }
+}
@@ -1,26 +1,25 @@
 use anyhow::Result;
 
+use crate::aws_profiles::read_shared_profiles;
 use crate::toml_parser::{read_aws_profiles, Profile};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Config {
     pub aws_profiles: Vec<Profile>,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            aws_profiles: vec![],
-        }
-    }
-}
-
 impl Config {
     pub fn new() -> Result<Self> {
-        let aws_profiles = read_aws_profiles()?;
+        let mut aws_profiles = read_shared_profiles()?;
+
+        // Profiles in the app config override the region of, or add to, the shared ones
+        for profile in read_aws_profiles()? {
+            match aws_profiles.iter_mut().find(|p| p.name == profile.name) {
+                Some(existing) => existing.region = profile.region,
+                None => aws_profiles.push(profile),
+            }
+        }
 
-        Ok(Self {
-            aws_profiles: aws_profiles,
-        })
+        Ok(Self { aws_profiles })
     }
 }
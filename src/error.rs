@@ -0,0 +1,220 @@
+use aws_sdk_cloudwatchlogs::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AwsErrorKind {
+    AuthExpired,
+    Throttled,
+    NotFound,
+    /// `action` is the IAM action named in the error message, e.g. `logs:FilterLogEvents`
+    PermissionDenied {
+        action: Option<String>,
+    },
+    Other,
+}
+
+/// An AWS error classified into the cases the UI can explain and offer to retry.
+#[derive(Debug, Clone)]
+pub struct AwsError {
+    pub kind: AwsErrorKind,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl AwsError {
+    pub fn new(code: Option<&str>, message: &str) -> Self {
+        Self {
+            kind: classify(code, message),
+            code: code.map(String::from),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn from_sdk<E, R>(err: SdkError<E, R>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + 'static,
+        R: fmt::Debug,
+    {
+        let message = match err.message() {
+            Some(message) => message.to_string(),
+            None => DisplayErrorContext(&err).to_string(),
+        };
+        Self::new(err.code(), &message)
+    }
+
+    /// Finds the `AwsError` inside an `anyhow::Error`, or wraps it as `Other`.
+    pub fn from_anyhow(err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<AwsError>() {
+            Some(aws_error) => aws_error.clone(),
+            None => Self::new(None, &format!("{:#}", err)),
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        match self.kind {
+            AwsErrorKind::AuthExpired => "Credentials expired",
+            AwsErrorKind::Throttled => "Request throttled",
+            AwsErrorKind::NotFound => "Not found",
+            AwsErrorKind::PermissionDenied { .. } => "Permission denied",
+            AwsErrorKind::Other => "AWS error",
+        }
+    }
+
+    pub fn hint(&self) -> String {
+        match &self.kind {
+            AwsErrorKind::AuthExpired => {
+                "Refresh your credentials (e.g. `aws sso login --profile <profile>`), then retry"
+                    .to_string()
+            }
+            AwsErrorKind::Throttled => "AWS is rate limiting requests; wait a moment and retry".to_string(),
+            AwsErrorKind::NotFound => {
+                "The log group or resource does not exist in this region; has the function ever logged?"
+                    .to_string()
+            }
+            AwsErrorKind::PermissionDenied { action: Some(action) } => {
+                format!("Your role is missing the IAM permission `{}`", action)
+            }
+            AwsErrorKind::PermissionDenied { action: None } => {
+                "Your role is missing an IAM permission for this request".to_string()
+            }
+            AwsErrorKind::Other => "Check your network connection and profile settings".to_string(),
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self.kind, AwsErrorKind::NotFound)
+    }
+}
+
+impl fmt::Display for AwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "{}: {}", code, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AwsError {}
+
+fn classify(code: Option<&str>, message: &str) -> AwsErrorKind {
+    match code.unwrap_or_default() {
+        "ExpiredToken"
+        | "ExpiredTokenException"
+        | "InvalidClientTokenId"
+        | "UnrecognizedClientException"
+        | "InvalidSignatureException" => return AwsErrorKind::AuthExpired,
+        "ThrottlingException"
+        | "Throttling"
+        | "TooManyRequestsException"
+        | "RequestLimitExceeded"
+        | "LimitExceededException" => return AwsErrorKind::Throttled,
+        "ResourceNotFoundException" | "NotFoundException" => return AwsErrorKind::NotFound,
+        "AccessDeniedException" | "AccessDenied" | "UnauthorizedOperation" => {
+            return AwsErrorKind::PermissionDenied {
+                action: missing_action(message),
+            }
+        }
+        _ => {}
+    }
+
+    // Credential provider failures never reach the service, so there is no code
+    let message = message.to_lowercase();
+    if message.contains("token has expired")
+        || message.contains("expired token")
+        || message.contains("sso session")
+        || message.contains("failed to load credentials")
+        || message.contains("no credentials")
+    {
+        AwsErrorKind::AuthExpired
+    } else {
+        AwsErrorKind::Other
+    }
+}
+
+/// Extracts `logs:FilterLogEvents` from "... is not authorized to perform: logs:FilterLogEvents on resource: ..."
+fn missing_action(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once("perform: ")?;
+    rest.split_whitespace()
+        .next()
+        .map(|action| action.trim_end_matches(['.', ',']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn denied(action: &str) -> AwsErrorKind {
+        AwsErrorKind::PermissionDenied {
+            action: Some(action.to_string()),
+        }
+    }
+
+    #[test]
+    fn classifies_errors() {
+        let cases = [
+            (Some("ThrottlingException"), "Rate exceeded", AwsErrorKind::Throttled),
+            (Some("TooManyRequestsException"), "", AwsErrorKind::Throttled),
+            (
+                Some("ExpiredTokenException"),
+                "The security token included in the request is expired",
+                AwsErrorKind::AuthExpired,
+            ),
+            (
+                None,
+                "the SSO session associated with this profile has expired or is otherwise invalid",
+                AwsErrorKind::AuthExpired,
+            ),
+            (
+                None,
+                "failed to load credentials: Token has expired and refresh failed",
+                AwsErrorKind::AuthExpired,
+            ),
+            (
+                Some("ResourceNotFoundException"),
+                "The specified log group does not exist.",
+                AwsErrorKind::NotFound,
+            ),
+            (
+                Some("AccessDeniedException"),
+                "User: arn:aws:sts::123456789012:assumed-role/dev/me is not authorized to perform: \
+                 logs:FilterLogEvents on resource: arn:aws:logs:eu-west-1:123456789012:log-group:/aws/lambda/orders",
+                denied("logs:FilterLogEvents"),
+            ),
+            (
+                Some("AccessDenied"),
+                "not authorized to perform: lambda:ListFunctions.",
+                denied("lambda:ListFunctions"),
+            ),
+            (
+                Some("AccessDeniedException"),
+                "Access denied",
+                AwsErrorKind::PermissionDenied { action: None },
+            ),
+            (Some("ServiceUnavailableException"), "Try again", AwsErrorKind::Other),
+            (None, "dispatch failure: connection refused", AwsErrorKind::Other),
+        ];
+        for (code, message, kind) in cases {
+            assert_eq!(classify(code, message), kind, "{:?} {}", code, message);
+        }
+    }
+
+    #[test]
+    fn finds_the_missing_action() {
+        let cases = [
+            (
+                "is not authorized to perform: logs:StartQuery on resource: *",
+                Some("logs:StartQuery"),
+            ),
+            (
+                "is not authorized to perform: tag:GetResources, because",
+                Some("tag:GetResources"),
+            ),
+            ("is not authorized to perform: ", None),
+            ("Access denied", None),
+        ];
+        for (message, action) in cases {
+            assert_eq!(missing_action(message).as_deref(), action, "{}", message);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::backend::LogEvent;
+use crate::utils::time_utils::DisplayTimezone;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    #[value(name = "jsonl")]
+    JsonLines,
+    /// `<ISO timestamp> <message>` per line
+    Logfile,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn all() -> Vec<ExportFormat> {
+        vec![
+            ExportFormat::JsonLines,
+            ExportFormat::Logfile,
+            ExportFormat::Csv,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::Logfile => "Logfile",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Logfile => "log",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Timestamps are written as RFC 3339 in `timezone`.
+pub fn write_events<W: Write>(
+    writer: &mut W,
+    events: &[LogEvent],
+    format: ExportFormat,
+    timezone: DisplayTimezone,
+) -> Result<()> {
+    let format_timestamp = |millis| timezone.rfc3339_millis(millis);
+    if format == ExportFormat::Csv {
+        writeln!(writer, "timestamp,ingestion_time,log_stream_name,message")?;
+    }
+
+    for event in events {
+        match format {
+            ExportFormat::JsonLines => {
+                let mut record = json!({
+                    "timestamp": format_timestamp(event.timestamp),
+                    "ingestion_time": format_timestamp(event.ingestion_time),
+                    "log_stream_name": event.log_stream_name,
+                    "message": event.message,
+                });
+                // Structured messages are also included parsed, so `jq` can reach into them
+                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&event.message) {
+                    if parsed.is_object() || parsed.is_array() {
+                        record["parsed"] = parsed;
+                    }
+                }
+                writeln!(writer, "{}", record)?;
+            }
+            ExportFormat::Logfile => {
+                writeln!(
+                    writer,
+                    "{} {}",
+                    format_timestamp(event.timestamp),
+                    event.message.trim_end()
+                )?;
+            }
+            ExportFormat::Csv => {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    format_timestamp(event.timestamp),
+                    format_timestamp(event.ingestion_time),
+                    csv_field(event.log_stream_name.as_deref().unwrap_or("")),
+                    csv_field(event.message.trim_end()),
+                )?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes `events` to `<dir>/<base_name>.<ext>` and returns the path written.
+pub fn export_to_file(
+    events: &[LogEvent],
+    format: ExportFormat,
+    timezone: DisplayTimezone,
+    dir: &Path,
+    base_name: &str,
+) -> Result<PathBuf> {
+    let safe_name: String = base_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = dir.join(format!("{}.{}", safe_name, format.extension()));
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    write_events(&mut writer, events, format, timezone)?;
+    Ok(path)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-15T12:00:00Z
+    const NOON: i64 = 1_792_065_600_000;
+
+    fn event(message: &str) -> LogEvent {
+        LogEvent {
+            event_id: None,
+            log_stream_name: Some("2026/10/15/[$LATEST]abc".to_string()),
+            timestamp: NOON,
+            ingestion_time: NOON + 1_500,
+            message: message.to_string(),
+        }
+    }
+
+    fn export(events: &[LogEvent], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        write_events(&mut out, events, format, DisplayTimezone::Utc).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let events = [
+            event("{\"level\":\"ERROR\",\"order\":42}\n"),
+            event("plain text"),
+            event("42"),
+        ];
+        let records: Vec<serde_json::Value> = export(&events, ExportFormat::JsonLines)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["timestamp"], "2026-10-15T12:00:00.000Z");
+        assert_eq!(records[0]["ingestion_time"], "2026-10-15T12:00:01.500Z");
+        assert_eq!(records[0]["log_stream_name"], "2026/10/15/[$LATEST]abc");
+        assert_eq!(
+            records[0]["message"],
+            "{\"level\":\"ERROR\",\"order\":42}\n"
+        );
+        assert_eq!(records[0]["parsed"]["order"], 42);
+        // Only objects and arrays are worth parsing
+        assert!(records[1].get("parsed").is_none());
+        assert!(records[2].get("parsed").is_none());
+    }
+
+    #[test]
+    fn writes_logfile_lines() {
+        let events = [event("START RequestId: 1\n"), event("done")];
+        assert_eq!(
+            export(&events, ExportFormat::Logfile),
+            "2026-10-15T12:00:00.000Z START RequestId: 1\n2026-10-15T12:00:00.000Z done\n"
+        );
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        let events = [event("plain"), event("total: 1,5 \"EUR\"\nnext line\n")];
+        assert_eq!(
+            export(&events, ExportFormat::Csv),
+            "timestamp,ingestion_time,log_stream_name,message\n\
+             2026-10-15T12:00:00.000Z,2026-10-15T12:00:01.500Z,2026/10/15/[$LATEST]abc,plain\n\
+             2026-10-15T12:00:00.000Z,2026-10-15T12:00:01.500Z,2026/10/15/[$LATEST]abc,\"total: 1,5 \"\"EUR\"\"\nnext line\"\n"
+        );
+
+        let cases = [
+            ("plain", "plain"),
+            ("a,b", "\"a,b\""),
+            ("say \"hi\"", "\"say \"\"hi\"\"\""),
+            ("two\nlines", "\"two\nlines\""),
+            ("cr\r", "\"cr\r\""),
+            ("", ""),
+        ];
+        for (value, quoted) in cases {
+            assert_eq!(csv_field(value), quoted, "{:?}", value);
+        }
+    }
+}
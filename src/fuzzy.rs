@@ -0,0 +1,352 @@
+const SCORE_MATCH: i64 = 16;
+const GAP_START: i64 = -3;
+const GAP_EXTENSION: i64 = -1;
+/// A match right after a separator or at the start of the text.
+const BONUS_BOUNDARY: i64 = 8;
+/// An uppercase letter after a lowercase one, or a digit after a letter.
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+/// The first character of a term counts more at a boundary.
+const FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TermKind {
+    Fuzzy,
+    Exact,
+    Prefix,
+    Suffix,
+    Equal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    kind: TermKind,
+    negated: bool,
+    case_sensitive: bool,
+    chars: Vec<char>,
+}
+
+/// A parsed filter query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuzzyQuery {
+    terms: Vec<Term>,
+}
+
+/// How well a text matched; higher scores rank first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices of the matched characters, ascending.
+    pub positions: Vec<usize>,
+}
+
+impl FuzzyQuery {
+    /// Parses fzf-style terms that all have to match: fuzzy by default,
+    /// `'` exact, `^` prefix, `$` suffix, `!` negated. Terms are case
+    /// insensitive unless they contain an uppercase letter.
+    pub fn parse(input: &str) -> Self {
+        let terms = input
+            .split_whitespace()
+            .filter_map(|word| {
+                let (negated, word) = match word.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, word),
+                };
+                let (kind, text) = if let Some(rest) = word.strip_prefix('\'') {
+                    (TermKind::Exact, rest)
+                } else {
+                    let (prefix, rest) = match word.strip_prefix('^') {
+                        Some(rest) => (true, rest),
+                        None => (false, word),
+                    };
+                    match (prefix, rest.strip_suffix('$')) {
+                        (true, Some(rest)) => (TermKind::Equal, rest),
+                        (true, None) => (TermKind::Prefix, rest),
+                        (false, Some(rest)) => (TermKind::Suffix, rest),
+                        // Negated fuzzy terms would exclude too much
+                        (false, None) if negated => (TermKind::Exact, rest),
+                        (false, None) => (TermKind::Fuzzy, rest),
+                    }
+                };
+                if text.is_empty() {
+                    return None;
+                }
+                Some(Term {
+                    kind,
+                    negated,
+                    case_sensitive: text.chars().any(char::is_uppercase),
+                    chars: text.chars().collect(),
+                })
+            })
+            .collect();
+
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Scores `text`, or returns `None` when any term doesn't match.
+    pub fn matches(&self, text: &str) -> Option<FuzzyMatch> {
+        let original: Vec<char> = text.chars().collect();
+        let lower: Vec<char> = original
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+
+        let mut result = FuzzyMatch::default();
+        for term in &self.terms {
+            let haystack = if term.case_sensitive {
+                &original
+            } else {
+                &lower
+            };
+            let found = match term.kind {
+                TermKind::Fuzzy => fuzzy_positions(&original, haystack, &term.chars),
+                TermKind::Exact => {
+                    find(haystack, &term.chars).map(|start| span(start, &term.chars))
+                }
+                TermKind::Prefix => haystack
+                    .starts_with(&term.chars)
+                    .then(|| span(0, &term.chars)),
+                TermKind::Suffix => haystack
+                    .ends_with(&term.chars)
+                    .then(|| span(haystack.len() - term.chars.len(), &term.chars)),
+                TermKind::Equal => (*haystack == term.chars).then(|| span(0, &term.chars)),
+            };
+
+            match (found, term.negated) {
+                (Some(_), true) | (None, false) => return None,
+                (None, true) => {}
+                (Some(positions), false) => {
+                    result.score += score_positions(&original, &positions);
+                    result.positions.extend(positions);
+                }
+            }
+        }
+
+        result.positions.sort_unstable();
+        result.positions.dedup();
+        Some(result)
+    }
+}
+
+fn span(start: usize, chars: &[char]) -> Vec<usize> {
+    (start..start + chars.len()).collect()
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn bonus_at(text: &[char], index: usize) -> i64 {
+    let Some(previous) = index.checked_sub(1).map(|i| text[i]) else {
+        return BONUS_BOUNDARY;
+    };
+    let current = text[index];
+    if !previous.is_alphanumeric() {
+        BONUS_BOUNDARY
+    } else if (previous.is_lowercase() && current.is_uppercase())
+        || (previous.is_alphabetic() && current.is_numeric())
+    {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+/// Scores matched positions the way `fuzzy_positions` optimizes them.
+fn score_positions(text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for &position in positions {
+        let bonus = bonus_at(text, position);
+        score += SCORE_MATCH
+            + match previous {
+                None => bonus * FIRST_CHAR_MULTIPLIER,
+                Some(previous) if previous + 1 == position => bonus.max(BONUS_CONSECUTIVE),
+                Some(previous) => {
+                    bonus + GAP_START + GAP_EXTENSION * (position - previous - 2) as i64
+                }
+            };
+        previous = Some(position);
+    }
+    score
+}
+
+/// Finds the best scoring positions of `pattern` as a subsequence of
+/// `haystack`, preferring boundaries and consecutive runs over gaps.
+fn fuzzy_positions(original: &[char], haystack: &[char], pattern: &[char]) -> Option<Vec<usize>> {
+    let (n, m) = (haystack.len(), pattern.len());
+    if m > n {
+        return None;
+    }
+
+    // cells[i][j]: the best score with pattern[i] matched at j, and where
+    // pattern[i - 1] was matched
+    let mut cells: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; n]; m];
+    for i in 0..m {
+        // The best earlier match of pattern[i - 1] at least one gap away,
+        // already charged for the gap up to j
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in i..n {
+            if i > 0 && j >= 2 {
+                gapped = gapped.map(|(score, from)| (score + GAP_EXTENSION, from));
+                if let Some((score, _)) = cells[i - 1][j - 2] {
+                    if gapped.is_none_or(|(best, _)| score + GAP_START > best) {
+                        gapped = Some((score + GAP_START, j - 2));
+                    }
+                }
+            }
+            if haystack[j] != pattern[i] {
+                continue;
+            }
+
+            let bonus = bonus_at(original, j);
+            cells[i][j] = if i == 0 {
+                Some((SCORE_MATCH + bonus * FIRST_CHAR_MULTIPLIER, 0))
+            } else {
+                let consecutive = cells[i - 1][j - 1]
+                    .map(|(score, _)| (score + SCORE_MATCH + bonus.max(BONUS_CONSECUTIVE), j - 1));
+                let after_gap = gapped.map(|(score, from)| (score + SCORE_MATCH + bonus, from));
+                match (consecutive, after_gap) {
+                    (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+                    (a, b) => a.or(b),
+                }
+            };
+        }
+    }
+
+    let (mut j, _) = cells[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, cell)| cell.map(|(score, _)| (j, score)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = cells[i][j].map_or(0, |(_, from)| from);
+    }
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNCTIONS: [&str; 6] = [
+        "orders-prod-processor",
+        "orders-staging-processor",
+        "payments-prod-api",
+        "pre-order-handler",
+        "users-dev-auth",
+        "Users-Prod-Auth",
+    ];
+
+    /// The functions matching `query`, best first.
+    fn filter(query: &str) -> Vec<&'static str> {
+        let query = FuzzyQuery::parse(query);
+        let mut matches: Vec<(i64, &str)> = FUNCTIONS
+            .iter()
+            .filter_map(|name| query.matches(name).map(|m| (m.score, *name)))
+            .collect();
+        matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn matches_characters_in_order() {
+        let found = FuzzyQuery::parse("ordprc")
+            .matches("orders-prod-processor")
+            .unwrap();
+        let chars: Vec<char> = "orders-prod-processor".chars().collect();
+        let matched: String = found.positions.iter().map(|&i| chars[i]).collect();
+        assert_eq!(matched, "ordprc");
+        assert_eq!(found.positions[..3], [0, 1, 2]);
+
+        assert!(FuzzyQuery::parse("ordprc")
+            .matches("orders-prod-api")
+            .is_none());
+        assert!(FuzzyQuery::parse("").is_empty());
+        assert_eq!(
+            FuzzyQuery::parse("").matches("anything"),
+            Some(FuzzyMatch::default())
+        );
+    }
+
+    #[test]
+    fn applies_operators() {
+        let cases: [(&str, &[&str]); 9] = [
+            (
+                "^orders",
+                &["orders-prod-processor", "orders-staging-processor"],
+            ),
+            ("^order$", &[]),
+            ("^users-dev-auth$", &["users-dev-auth"]),
+            ("handler$", &["pre-order-handler"]),
+            (
+                "'prod",
+                &[
+                    "orders-prod-processor",
+                    "payments-prod-api",
+                    "Users-Prod-Auth",
+                ],
+            ),
+            ("'rod-p", &["orders-prod-processor"]),
+            ("^orders !staging", &["orders-prod-processor"]),
+            // Negated terms are substrings, not fuzzy
+            (
+                "processor !opr",
+                &["orders-prod-processor", "orders-staging-processor"],
+            ),
+            // An operator alone is no term
+            ("^ ' ! $", &FUNCTIONS),
+        ];
+        for (query, expected) in cases {
+            let mut found = filter(query);
+            found.sort_unstable();
+            let mut expected = expected.to_vec();
+            expected.sort_unstable();
+            assert_eq!(found, expected, "{}", query);
+        }
+
+        // Without the quote, `prod` also matches spread out characters
+        assert!(filter("prod").contains(&"pre-order-handler"));
+        assert!(!filter("'prod").contains(&"pre-order-handler"));
+    }
+
+    #[test]
+    fn is_case_sensitive_only_with_uppercase() {
+        assert_eq!(filter("^users"), ["users-dev-auth", "Users-Prod-Auth"]);
+        assert_eq!(filter("^Users"), ["Users-Prod-Auth"]);
+        assert_eq!(filter("'Prod"), ["Users-Prod-Auth"]);
+        assert!(filter("PROD").is_empty());
+        assert_eq!(filter("!Prod").len(), FUNCTIONS.len() - 1);
+    }
+
+    #[test]
+    fn ranks_boundaries_and_runs_first() {
+        let query = FuzzyQuery::parse("proc");
+        let score = |text: &str| query.matches(text).unwrap().score;
+        // Consecutive at a word start, then consecutive inside a word, then
+        // spread out inside words
+        assert!(score("orders-processor") > score("orders-reprocessor"));
+        assert!(score("orders-reprocessor") > score("xpxrxoxc"));
+        // Word starts make up for gaps
+        assert!(score("p-r-o-c") > score("orders-reprocessor"));
+
+        assert_eq!(
+            filter("prod"),
+            [
+                "orders-prod-processor",
+                "payments-prod-api",
+                "Users-Prod-Auth",
+                "pre-order-handler"
+            ]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Result};
+use chrono::{Local, Utc};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::app_state::log_viewer::LogViewer;
+use crate::backend::{BackendKind, LogSource};
+use crate::cli::LogsArgs;
+use crate::export::write_events;
+use crate::recent::RecentLimit;
+use crate::toml_parser::Profile;
+use crate::utils::file_utils::cache_functions;
+use crate::utils::time_utils::DisplayTimezone;
+
+/// Picks the profile named on the command line, or the first available one.
+pub fn resolve_profile(
+    backend_kind: &BackendKind,
+    name: Option<&str>,
+    region: Option<&str>,
+) -> Result<Profile> {
+    let profiles = backend_kind.profiles()?;
+    let available = profiles
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut profile = match name {
+        Some(name) => profiles
+            .into_iter()
+            .find(|p| p.name == name)
+            .or_else(|| {
+                // Not in our config, but the SDK can still resolve it if a region is given
+                region.map(|region| Profile {
+                    name: name.to_string(),
+                    region: region.to_string(),
+                })
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown profile '{}' (available: {}), pass --region to use it anyway",
+                    name,
+                    available
+                )
+            })?,
+        None => profiles
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No AWS profiles configured, pass --profile and --region"))?,
+    };
+
+    if let Some(region) = region {
+        profile.region = region.to_string();
+    }
+
+    Ok(profile)
+}
+
+pub async fn print_functions(backend_kind: &BackendKind, profile: &Profile) -> Result<()> {
+    let backend = backend_kind.connect(profile).await?;
+    let mut functions = backend.functions.list_functions().await?;
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    cache_functions(
+        &profile.name,
+        &profile.region,
+        Utc::now().timestamp_millis(),
+        &functions,
+    )?;
+
+    let mut stdout = io::stdout().lock();
+    for function in functions {
+        writeln!(stdout, "{}", function.name)?;
+    }
+    Ok(())
+}
+
+pub async fn print_logs(
+    backend_kind: &BackendKind,
+    profile: &Profile,
+    args: &LogsArgs,
+    timezone: DisplayTimezone,
+) -> Result<()> {
+    let now = Local::now();
+    let (from_date, to_date) = match &args.range {
+        Some(range) => timezone.parse_time_range(range, now)?,
+        None => (
+            timezone.parse_time_arg(&args.since, now)?,
+            timezone.parse_time_arg(&args.until, now)?,
+        ),
+    };
+    if from_date > to_date {
+        return Err(anyhow!("--since must be before --until"));
+    }
+
+    let backend = backend_kind.connect(profile).await?;
+    let source = match (&args.function, &args.log_group) {
+        (_, Some(log_group)) => LogSource::log_group(log_group),
+        (Some(function), None) => LogSource::lambda(function),
+        (None, None) => return Err(anyhow!("Pass a function name or --log-group")),
+    };
+    let recent = match (args.recent, args.recent_invocations) {
+        (Some(0), _) | (_, Some(0)) => return Err(anyhow!("The count must be at least 1")),
+        (Some(count), _) => Some(RecentLimit::Events(count)),
+        (_, Some(count)) => Some(RecentLimit::Invocations(count)),
+        (None, None) => None,
+    };
+    let mut log_viewer = match recent {
+        Some(limit) => LogViewer::recent(backend.logs, source, limit),
+        None => LogViewer::new(backend.logs, source, from_date, to_date),
+    };
+    log_viewer.timezone = timezone;
+    log_viewer.server_filter = args.filter_pattern.clone().unwrap_or_default();
+    if let Some(filter) = &args.filter {
+        log_viewer.filter_input = filter.clone();
+    }
+    log_viewer.load_logs().await?;
+    if recent.is_some() {
+        // The range that was found goes to stderr, to keep stdout parseable
+        if let Some(status) = &log_viewer.status_message {
+            eprintln!("{}", status);
+        }
+    }
+
+    match &args.output {
+        Some(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            write_events(
+                &mut writer,
+                &log_viewer.filtered_logs,
+                args.format,
+                timezone,
+            )
+        }
+        None => write_events(
+            &mut io::stdout().lock(),
+            &log_viewer.filtered_logs,
+            args.format,
+            timezone,
+        ),
+    }
+}
@@ -0,0 +1,716 @@
+use std::collections::HashMap;
+
+use crate::backend::LogEvent;
+
+// Keys structured loggers use for the Lambda request ID
+const JSON_REQUEST_ID_KEYS: [&str; 4] = [
+    "requestId",
+    "request_id",
+    "awsRequestId",
+    "function_request_id",
+];
+
+// Messages the runtimes log when a function runs out of memory
+const OUT_OF_MEMORY_MARKERS: [&str; 4] = [
+    "JavaScript heap out of memory",
+    "java.lang.OutOfMemoryError",
+    "MemoryError",
+    "Runtime.OutOfMemory",
+];
+
+/// Platform conditions worth spotting in an invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Problems {
+    pub cold_start: bool,
+    pub timeout: bool,
+    pub runtime_exited: bool,
+    pub out_of_memory: bool,
+}
+
+impl Problems {
+    fn merge(&mut self, other: Problems) {
+        self.cold_start |= other.cold_start;
+        self.timeout |= other.timeout;
+        self.runtime_exited |= other.runtime_exited;
+        self.out_of_memory |= other.out_of_memory;
+    }
+
+    /// The invocation failed in a way the function's own logs don't show;
+    /// a cold start alone is not a failure.
+    pub fn is_failure(&self) -> bool {
+        self.timeout || self.runtime_exited || self.out_of_memory
+    }
+
+    /// Short tags, most severe first, e.g. `["OOM", "COLD"]`.
+    pub fn tags(&self) -> Vec<&'static str> {
+        [
+            (self.out_of_memory, "OOM"),
+            (self.timeout, "TIMEOUT"),
+            (self.runtime_exited, "EXITED"),
+            (self.cold_start, "COLD"),
+        ]
+        .into_iter()
+        .filter_map(|(set, tag)| set.then_some(tag))
+        .collect()
+    }
+}
+
+/// What a single line tells about its invocation.
+pub fn line_problems(message: &str) -> Problems {
+    let mut problems = Problems {
+        timeout: message.contains("Task timed out after"),
+        runtime_exited: message.contains("Runtime exited"),
+        out_of_memory: OUT_OF_MEMORY_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker)),
+        ..Default::default()
+    };
+
+    if let Some(report) = ReportMetrics::parse(message) {
+        problems.cold_start = report.is_cold_start();
+        problems.timeout |= report.status.as_deref() == Some("timeout");
+        // Running into the memory limit gets the environment killed
+        problems.out_of_memory |= matches!(
+            (report.max_memory_used_mb, report.memory_size_mb),
+            (Some(used), Some(size)) if used >= size
+        );
+    }
+    problems
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvocationStatus {
+    /// No END or REPORT line among the loaded events
+    Incomplete,
+    Completed,
+    Failed,
+    TimedOut,
+}
+
+impl InvocationStatus {
+    pub fn display_name(&self) -> &str {
+        match self {
+            InvocationStatus::Incomplete => "Incomplete",
+            InvocationStatus::Completed => "Completed",
+            InvocationStatus::Failed => "Failed",
+            InvocationStatus::TimedOut => "Timed out",
+        }
+    }
+}
+
+/// The metrics of a `REPORT RequestId: ...` line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportMetrics {
+    pub duration_ms: f64,
+    pub billed_duration_ms: Option<f64>,
+    pub memory_size_mb: Option<f64>,
+    pub max_memory_used_mb: Option<f64>,
+    /// Only present on cold starts
+    pub init_duration_ms: Option<f64>,
+    /// How the invocation ended, e.g. `timeout`; newer runtimes only
+    pub status: Option<String>,
+}
+
+impl ReportMetrics {
+    pub fn parse(message: &str) -> Option<Self> {
+        if !message.starts_with("REPORT RequestId:") {
+            return None;
+        }
+        // Values look like `12.34 ms` or `128 MB`
+        let number = |name: &str| {
+            report_field(message, name)
+                .and_then(|value| value.split_whitespace().next())
+                .and_then(|value| value.parse().ok())
+        };
+
+        Some(Self {
+            duration_ms: number("Duration")?,
+            billed_duration_ms: number("Billed Duration"),
+            memory_size_mb: number("Memory Size"),
+            max_memory_used_mb: number("Max Memory Used"),
+            init_duration_ms: number("Init Duration"),
+            status: report_field(message, "Status").map(String::from),
+        })
+    }
+
+    pub fn is_cold_start(&self) -> bool {
+        self.init_duration_ms.is_some()
+    }
+}
+
+/// Aggregates over the invocations of a range, as shown in the stats panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InvocationStats {
+    pub invocations: usize,
+    /// Invocations with a REPORT line, the ones the durations are taken from
+    pub reported: usize,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub max_memory_used_mb: Option<f64>,
+    pub memory_size_mb: Option<f64>,
+    pub cold_starts: usize,
+    pub timeouts: usize,
+}
+
+impl InvocationStats {
+    pub fn from_invocations(invocations: &[Invocation]) -> Self {
+        let with_id: Vec<&Invocation> = invocations
+            .iter()
+            .filter(|invocation| invocation.request_id.is_some())
+            .collect();
+        let reports: Vec<&ReportMetrics> = with_id
+            .iter()
+            .filter_map(|invocation| invocation.report.as_ref())
+            .collect();
+
+        let mut durations: Vec<f64> = reports.iter().map(|r| r.duration_ms).collect();
+        durations.sort_by(f64::total_cmp);
+
+        Self {
+            invocations: with_id.len(),
+            reported: reports.len(),
+            p50_ms: percentile(&durations, 0.50),
+            p90_ms: percentile(&durations, 0.90),
+            p99_ms: percentile(&durations, 0.99),
+            max_ms: durations.last().copied(),
+            max_memory_used_mb: reports
+                .iter()
+                .filter_map(|r| r.max_memory_used_mb)
+                .reduce(f64::max),
+            memory_size_mb: reports
+                .iter()
+                .filter_map(|r| r.memory_size_mb)
+                .reduce(f64::max),
+            cold_starts: reports.iter().filter(|r| r.is_cold_start()).count(),
+            timeouts: with_id
+                .iter()
+                .filter(|invocation| invocation.status == InvocationStatus::TimedOut)
+                .count(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1) - 1).copied()
+}
+
+/// The events of one Lambda request, in the order they were logged. Events
+/// without a request ID each make up an invocation of their own.
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub request_id: Option<String>,
+    pub log_stream_name: Option<String>,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub report: Option<ReportMetrics>,
+    pub status: InvocationStatus,
+    pub has_error: bool,
+    pub problems: Problems,
+    pub events: Vec<LogEvent>,
+}
+
+impl Invocation {
+    fn new(request_id: Option<String>, event: &LogEvent) -> Self {
+        Self {
+            request_id,
+            log_stream_name: event.log_stream_name.clone(),
+            start_time: event.timestamp,
+            end_time: event.timestamp,
+            report: None,
+            status: InvocationStatus::Incomplete,
+            has_error: false,
+            problems: Problems::default(),
+            events: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, event: &LogEvent) {
+        let message = event.message.as_str();
+        self.start_time = self.start_time.min(event.timestamp);
+        self.end_time = self.end_time.max(event.timestamp);
+        self.has_error |= is_error_line(message);
+        self.problems.merge(line_problems(message));
+
+        if let Some(report) = ReportMetrics::parse(message) {
+            // Newer runtimes report how the invocation ended
+            self.status = match report.status.as_deref() {
+                Some("timeout") => InvocationStatus::TimedOut,
+                Some("error") => InvocationStatus::Failed,
+                _ if self.status == InvocationStatus::Incomplete => InvocationStatus::Completed,
+                _ => self.status,
+            };
+            self.report = Some(report);
+        } else if message.starts_with("END RequestId:")
+            && self.status == InvocationStatus::Incomplete
+        {
+            self.status = InvocationStatus::Completed;
+        } else if message.contains("Task timed out after") {
+            self.status = InvocationStatus::TimedOut;
+        } else if message.contains("Runtime exited") {
+            self.status = InvocationStatus::Failed;
+        }
+
+        self.events.push(event.clone());
+    }
+}
+
+/// Groups events by request ID, ordered by when each invocation started.
+pub fn group_invocations(events: &[LogEvent]) -> Vec<Invocation> {
+    let mut invocations: Vec<Invocation> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (event, request_id) in events.iter().zip(request_ids(events)) {
+        let position = match request_id {
+            Some(request_id) => *positions.entry(request_id.clone()).or_insert_with(|| {
+                invocations.push(Invocation::new(Some(request_id), event));
+                invocations.len() - 1
+            }),
+            None => {
+                invocations.push(Invocation::new(None, event));
+                invocations.len() - 1
+            }
+        };
+        invocations[position].add(event);
+    }
+
+    invocations.sort_by_key(|invocation| invocation.start_time);
+    invocations
+}
+
+/// The request ID of each event. Lines that don't carry one, like plain
+/// `print` output, belong to the invocation running in their stream at the
+/// time, as an execution environment handles one request at a time.
+pub fn request_ids(events: &[LogEvent]) -> Vec<Option<String>> {
+    let mut running = HashMap::new();
+    events
+        .iter()
+        .map(|event| attribute_request(&mut running, event))
+        .collect()
+}
+
+/// `running` maps each stream to the request it is executing.
+fn attribute_request(running: &mut HashMap<String, String>, event: &LogEvent) -> Option<String> {
+    let stream = event.log_stream_name.as_deref().unwrap_or_default();
+    let message = event.message.as_str();
+
+    if let Some(request_id) = marker_request_id(message, "START RequestId: ") {
+        running.insert(stream.to_string(), request_id.clone());
+        Some(request_id)
+    } else if let Some(request_id) = marker_request_id(message, "REPORT RequestId: ") {
+        // REPORT is the last line Lambda writes for a request
+        running.remove(stream);
+        Some(request_id)
+    } else {
+        // `END RequestId: ...`, or `RequestId: ... Error: Runtime exited ...`
+        marker_request_id(message.trim_start_matches("END "), "RequestId: ")
+            .or_else(|| explicit_request_id(message))
+            .or_else(|| running.get(stream).cloned())
+    }
+}
+
+/// Request IDs and problems of the events loaded so far, updated as pages
+/// arrive so the list can tag events without regrouping everything.
+#[derive(Debug, Default)]
+pub struct RequestIndex {
+    running: HashMap<String, String>,
+    requests: HashMap<String, String>,
+    problems: HashMap<String, Problems>,
+}
+
+impl RequestIndex {
+    /// Adds events that follow the ones added so far.
+    pub fn add(&mut self, events: &[LogEvent]) {
+        for event in events {
+            let Some(request_id) = attribute_request(&mut self.running, event) else {
+                continue;
+            };
+            let problems = line_problems(&event.message);
+            if problems != Problems::default() {
+                self.problems
+                    .entry(request_id.clone())
+                    .or_default()
+                    .merge(problems);
+            }
+            self.requests.insert(event.dedup_key(), request_id);
+        }
+    }
+
+    /// Starts over, e.g. after older events were inserted at the front.
+    pub fn rebuild(&mut self, events: &[LogEvent]) {
+        *self = Self::default();
+        self.add(events);
+    }
+
+    pub fn request_id(&self, event: &LogEvent) -> Option<&str> {
+        self.requests.get(&event.dedup_key()).map(String::as_str)
+    }
+
+    /// The problems of the invocation the event belongs to.
+    pub fn problems(&self, event: &LogEvent) -> Problems {
+        self.request_id(event)
+            .and_then(|request_id| self.problems.get(request_id))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A tab separated `Name: value` field of a REPORT line, e.g. `Duration`.
+pub fn report_field<'a>(message: &'a str, name: &str) -> Option<&'a str> {
+    message.split('\t').find_map(|field| {
+        field
+            .trim()
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix(": "))
+    })
+}
+
+/// The `START` line the Lambda platform writes when an invocation begins.
+pub fn is_invocation_start(message: &str) -> bool {
+    marker_request_id(message, "START RequestId: ").is_some()
+}
+
+fn marker_request_id(message: &str, marker: &str) -> Option<String> {
+    message
+        .strip_prefix(marker)
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(String::from)
+}
+
+/// Request IDs written right after the timestamp by the runtimes' default
+/// log format (`<time>\t<id>\tINFO\t...`, Python's `[INFO]\t<time>\t<id>\t...`)
+/// and by the platform (`<time> <id> Task timed out...`), or by structured
+/// loggers. IDs elsewhere in a line are the application's own.
+fn explicit_request_id(message: &str) -> Option<String> {
+    let mut fields = message.split(['\t', ' ']);
+    let mut time = fields.next().unwrap_or_default();
+    if time.starts_with('[') && time.ends_with(']') {
+        time = fields.next().unwrap_or_default();
+    }
+    if chrono::DateTime::parse_from_rfc3339(time).is_ok() {
+        if let Some(field) = fields.next().filter(|field| is_request_id(field)) {
+            return Some(field.to_string());
+        }
+    }
+
+    if !message.starts_with('{') {
+        return None;
+    }
+    let json = serde_json::from_str::<serde_json::Value>(message).ok()?;
+    JSON_REQUEST_ID_KEYS
+        .iter()
+        .find_map(|key| json.get(key).and_then(|value| value.as_str()))
+        .map(String::from)
+}
+
+fn is_request_id(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Error lines of the common runtime and logger formats.
+pub fn is_error_line(message: &str) -> bool {
+    let level_field = message
+        .split('\t')
+        .take(4)
+        .any(|field| matches!(field, "ERROR" | "[ERROR]" | "FATAL" | "[FATAL]"));
+    if level_field || message.starts_with("Traceback (most recent call last)") {
+        return true;
+    }
+
+    if !message.starts_with('{') {
+        return false;
+    }
+    serde_json::from_str::<serde_json::Value>(message)
+        .ok()
+        .and_then(|json| {
+            ["level", "levelname", "severity"]
+                .iter()
+                .find_map(|key| json.get(key).and_then(|value| value.as_str()))
+                .map(|level| {
+                    matches!(
+                        level.to_ascii_uppercase().as_str(),
+                        "ERROR" | "FATAL" | "CRITICAL"
+                    )
+                })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST_ID: &str = "8f5a0c5e-1b8e-4e7e-9f3a-2d1c0b9a8e7f";
+
+    fn report(fields: &str) -> String {
+        format!("REPORT RequestId: {}\t{}\t", REQUEST_ID, fields)
+    }
+
+    fn event(timestamp: i64, message: &str) -> LogEvent {
+        LogEvent {
+            event_id: Some(timestamp.to_string()),
+            log_stream_name: Some("2026/10/15/[$LATEST]0123456789abcdef".to_string()),
+            timestamp,
+            ingestion_time: timestamp,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_report_lines() {
+        let cold = report(
+            "Duration: 102.25 ms\tBilled Duration: 103 ms\tMemory Size: 128 MB\tMax Memory Used: 67 MB\tInit Duration: 231.90 ms",
+        );
+        assert_eq!(
+            ReportMetrics::parse(&cold),
+            Some(ReportMetrics {
+                duration_ms: 102.25,
+                billed_duration_ms: Some(103.0),
+                memory_size_mb: Some(128.0),
+                max_memory_used_mb: Some(67.0),
+                init_duration_ms: Some(231.9),
+                status: None,
+            })
+        );
+
+        let warm = report(
+            "Duration: 3.51 ms\tBilled Duration: 4 ms\tMemory Size: 128 MB\tMax Memory Used: 68 MB",
+        );
+        let metrics = ReportMetrics::parse(&warm).unwrap();
+        assert_eq!(metrics.duration_ms, 3.51);
+        assert_eq!(metrics.billed_duration_ms, Some(4.0));
+        assert_eq!(metrics.init_duration_ms, None);
+        assert!(!metrics.is_cold_start());
+        assert!(ReportMetrics::parse(&cold).unwrap().is_cold_start());
+
+        for message in [
+            "END RequestId: 8f5a0c5e-1b8e-4e7e-9f3a-2d1c0b9a8e7f",
+            "REPORT something else",
+            "  REPORT RequestId: 8f5a0c5e-1b8e-4e7e-9f3a-2d1c0b9a8e7f\tDuration: 1 ms",
+            &report("Billed Duration: 4 ms\tMemory Size: 128 MB"),
+            &report("Duration: n/a"),
+        ] {
+            assert_eq!(ReportMetrics::parse(message), None, "{}", message);
+        }
+    }
+
+    #[test]
+    fn spots_timeouts() {
+        let timeout = report(
+            "Duration: 3000.00 ms\tBilled Duration: 3000 ms\tMemory Size: 128 MB\tMax Memory Used: 70 MB\tStatus: timeout",
+        );
+        assert_eq!(
+            ReportMetrics::parse(&timeout).unwrap().status.as_deref(),
+            Some("timeout")
+        );
+        assert!(line_problems(&timeout).timeout);
+
+        let task_timed_out = format!(
+            "2026-10-15T12:00:03.005Z {} Task timed out after 3.00 seconds",
+            REQUEST_ID
+        );
+        assert!(line_problems(&task_timed_out).timeout);
+
+        // Older runtimes only write the `Task timed out` line
+        let invocations = group_invocations(&[
+            event(0, &format!("START RequestId: {} Version: $LATEST", REQUEST_ID)),
+            event(3_005, &task_timed_out),
+            event(3_006, &format!("END RequestId: {}", REQUEST_ID)),
+            event(
+                3_007,
+                &report("Duration: 3000.00 ms\tBilled Duration: 3000 ms\tMemory Size: 128 MB\tMax Memory Used: 70 MB"),
+            ),
+        ]);
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].status, InvocationStatus::TimedOut);
+        assert_eq!(invocations[0].problems.tags(), ["TIMEOUT"]);
+        assert_eq!(invocations[0].report.as_ref().unwrap().duration_ms, 3000.0);
+    }
+
+    #[test]
+    fn spots_out_of_memory() {
+        let out_of_memory = report(
+            "Duration: 812.43 ms\tBilled Duration: 813 ms\tMemory Size: 128 MB\tMax Memory Used: 128 MB\tInit Duration: 190.12 ms\tStatus: error\tError Type: Runtime.OutOfMemory",
+        );
+        let metrics = ReportMetrics::parse(&out_of_memory).unwrap();
+        assert_eq!(metrics.status.as_deref(), Some("error"));
+        assert_eq!(metrics.max_memory_used_mb, metrics.memory_size_mb);
+        assert_eq!(line_problems(&out_of_memory).tags(), ["OOM", "COLD"]);
+
+        // Reaching the limit is enough, without a status
+        let at_limit = report(
+            "Duration: 812.43 ms\tBilled Duration: 813 ms\tMemory Size: 128 MB\tMax Memory Used: 128 MB",
+        );
+        assert!(line_problems(&at_limit).out_of_memory);
+
+        let invocations = group_invocations(&[
+            event(0, &format!("START RequestId: {} Version: $LATEST", REQUEST_ID)),
+            event(
+                800,
+                &format!(
+                    "RequestId: {} Error: Runtime exited with error: signal: killed\nRuntime.ExitError",
+                    REQUEST_ID
+                ),
+            ),
+            event(812, &format!("END RequestId: {}", REQUEST_ID)),
+            event(813, &out_of_memory),
+        ]);
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].status, InvocationStatus::Failed);
+        assert_eq!(invocations[0].problems.tags(), ["OOM", "EXITED", "COLD"]);
+    }
+
+    #[test]
+    fn tags_cold_starts() {
+        let warm = report(
+            "Duration: 3.51 ms\tBilled Duration: 4 ms\tMemory Size: 128 MB\tMax Memory Used: 68 MB",
+        );
+        let cold = report(
+            "Duration: 102.25 ms\tBilled Duration: 103 ms\tMemory Size: 128 MB\tMax Memory Used: 67 MB\tInit Duration: 231.90 ms",
+        );
+        assert_eq!(line_problems(&warm), Problems::default());
+        assert_eq!(line_problems(&cold).tags(), ["COLD"]);
+        assert!(!line_problems(&cold).is_failure());
+    }
+
+    #[test]
+    fn indexes_problems_of_whole_requests() {
+        let other = "0b1c2d3e-4f50-4617-8293-a4b5c6d7e8f9";
+        let events = [
+            event(
+                0,
+                &format!("START RequestId: {} Version: $LATEST", REQUEST_ID),
+            ),
+            event(1, "charging card"),
+            event(
+                3_005,
+                &format!(
+                    "2026-10-15T12:00:03.005Z {} Task timed out after 3.00 seconds",
+                    REQUEST_ID
+                ),
+            ),
+            event(3_006, &format!("END RequestId: {}", REQUEST_ID)),
+            event(
+                4_000,
+                &format!("START RequestId: {} Version: $LATEST", other),
+            ),
+            event(4_001, "charging card"),
+            event(4_002, &format!("END RequestId: {}", other)),
+        ];
+        let mut index = RequestIndex::default();
+        index.add(&events[..2]);
+        // Lines before the failure learn about it once it is added
+        assert_eq!(index.problems(&events[1]), Problems::default());
+        index.add(&events[2..]);
+
+        for event in &events[..4] {
+            assert_eq!(index.request_id(event), Some(REQUEST_ID));
+            assert!(index.problems(event).timeout, "{}", event.message);
+        }
+        for event in &events[4..] {
+            assert_eq!(index.request_id(event), Some(other));
+            assert_eq!(index.problems(event), Problems::default());
+        }
+    }
+
+    #[test]
+    fn finds_request_ids_after_the_timestamp() {
+        let cases = [
+            (
+                format!(
+                    "2026-10-15T12:00:00.100Z\t{}\tINFO\tcharging card",
+                    REQUEST_ID
+                ),
+                true,
+            ),
+            (
+                format!(
+                    "[ERROR]\t2026-10-15T12:00:00.100Z\t{}\tcard declined",
+                    REQUEST_ID
+                ),
+                true,
+            ),
+            (
+                format!(
+                    "2026-10-15T12:00:03.005Z {} Task timed out after 3.00 seconds",
+                    REQUEST_ID
+                ),
+                true,
+            ),
+            (
+                format!("{{\"level\":\"INFO\",\"requestId\":\"{}\"}}", REQUEST_ID),
+                true,
+            ),
+            (format!("order {} shipped", REQUEST_ID), false),
+            (format!("{} order shipped", REQUEST_ID), false),
+            (format!("INFO\t{}\tshipped", REQUEST_ID), false),
+            (
+                format!("2026-10-15T12:00:00.100Z\tINFO\t{}", REQUEST_ID),
+                false,
+            ),
+        ];
+        for (message, found) in cases {
+            let expected = found.then(|| REQUEST_ID.to_string());
+            assert_eq!(explicit_request_id(&message), expected, "{}", message);
+        }
+    }
+
+    #[test]
+    fn attributes_lines_of_concurrent_requests_by_stream() {
+        let other = "0b1c2d3e-4f50-4617-8293-a4b5c6d7e8f9";
+        let order = "6c1d9e2f-3a4b-4c5d-8e6f-7a8b9c0d1e2f";
+        let in_stream = |stream: &str, timestamp: i64, message: String| LogEvent {
+            log_stream_name: Some(stream.to_string()),
+            ..event(timestamp, &message)
+        };
+        let (a, b) = ("2026/10/15/[$LATEST]aaaa", "2026/10/15/[$LATEST]bbbb");
+        let events = [
+            in_stream(
+                a,
+                0,
+                format!("START RequestId: {} Version: $LATEST", REQUEST_ID),
+            ),
+            in_stream(b, 1, format!("START RequestId: {} Version: $LATEST", other)),
+            in_stream(a, 2, "charging card".to_string()),
+            in_stream(b, 3, format!("order {} shipped", order)),
+            in_stream(
+                a,
+                4,
+                format!("2026-10-15T12:00:00.004Z\t{}\tINFO\tcharged", REQUEST_ID),
+            ),
+            in_stream(b, 5, "sending email".to_string()),
+            in_stream(a, 6, format!("END RequestId: {}", REQUEST_ID)),
+            in_stream(a, 7, report("Duration: 7.00 ms")),
+            in_stream(b, 8, "still sending".to_string()),
+            in_stream(a, 9, "between requests".to_string()),
+        ];
+        let expected = [
+            Some(REQUEST_ID),
+            Some(other),
+            Some(REQUEST_ID),
+            Some(other),
+            Some(REQUEST_ID),
+            Some(other),
+            Some(REQUEST_ID),
+            Some(REQUEST_ID),
+            Some(other),
+            None,
+        ];
+
+        let ids = request_ids(&events);
+        let mut index = RequestIndex::default();
+        index.add(&events[..5]);
+        index.add(&events[5..]);
+        for ((event, id), expected) in events.iter().zip(&ids).zip(expected) {
+            assert_eq!(id.as_deref(), expected, "{}", event.message);
+            assert_eq!(index.request_id(event), expected, "{}", event.message);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+
+use crate::backend::{LogBackend, LogEvent, LogQuery};
+use crate::invocations::is_invocation_start;
+
+pub const DEFAULT_RECENT_EVENTS: usize = 100;
+
+// Streams looked at for the newest event; DescribeLogStreams returns them newest first
+const STREAMS_CHECKED: usize = 50;
+// The window searched back from the newest event starts at a minute and
+// grows four times each step, up to about a year unless every stream was
+// listed and their first events are known
+const FIRST_WINDOW_MS: i64 = 60_000;
+const WINDOW_GROWTH: i64 = 4;
+const MAX_WINDOW_MS: i64 = 366 * 24 * 60 * 60_000;
+// A window with more events than needed is halved towards its end, down to
+// this width
+const MIN_WINDOW_MS: i64 = 1_000;
+
+/// How much of a log group's latest activity to load, independent of any
+/// fixed range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecentLimit {
+    Events(usize),
+    /// Counted by Lambda's `START` lines.
+    Invocations(usize),
+}
+
+impl RecentLimit {
+    /// `recent`, `recent 200`, `recent 200 events` or `recent 20
+    /// invocations`; `None` for other expressions.
+    pub fn parse(input: &str) -> Option<Result<Self>> {
+        let lower = input.trim().to_ascii_lowercase();
+        let rest = lower.strip_prefix("recent")?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+
+        let mut words = rest.split_whitespace();
+        let count = match words.next() {
+            None => return Some(Ok(Self::Events(DEFAULT_RECENT_EVENTS))),
+            Some(count) => match count.parse::<usize>() {
+                Ok(count) if count > 0 => count,
+                _ => return Some(Err(anyhow!("invalid count '{}' in '{}'", count, input))),
+            },
+        };
+        Some(match (words.next(), words.next()) {
+            (None | Some("events"), None) => Ok(Self::Events(count)),
+            (Some("invocations"), None) => Ok(Self::Invocations(count)),
+            _ => Err(anyhow!(
+                "use `recent <count>` or `recent <count> invocations`, not '{}'",
+                input
+            )),
+        })
+    }
+
+    /// e.g. `last 100 events`
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Events(1) => "last event".to_string(),
+            Self::Events(count) => format!("last {} events", count),
+            Self::Invocations(1) => "last invocation".to_string(),
+            Self::Invocations(count) => format!("last {} invocations", count),
+        }
+    }
+}
+
+/// The newest events of a log group and the range they were found in.
+#[derive(Debug)]
+pub struct RecentLogs {
+    pub events: Vec<LogEvent>,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+/// Finds the newest event through `DescribeLogStreams` ordered by last
+/// event time, then searches back from it in growing windows until `limit`
+/// is reached. The first window reaches up to `now`, as a stream's last
+/// event time can lag behind. Pages come oldest first, so a window that
+/// turns out to hold more than needed is dropped and its later half
+/// searched instead, keeping the cost in line with `limit`.
+pub async fn find_recent_logs(
+    backend: &dyn LogBackend,
+    log_group_name: &str,
+    filter_pattern: Option<String>,
+    limit: RecentLimit,
+    now: i64,
+) -> Result<RecentLogs> {
+    let streams = backend
+        .describe_log_streams(log_group_name, STREAMS_CHECKED)
+        .await?;
+    let newest = streams
+        .iter()
+        .filter_map(|stream| stream.last_event_time)
+        .max()
+        .ok_or_else(|| anyhow!("{} has no log events", log_group_name))?;
+    // With every stream listed, nothing is older than their first events
+    let oldest = (streams.len() < STREAMS_CHECKED)
+        .then(|| {
+            streams
+                .iter()
+                .filter_map(|stream| stream.first_event_time)
+                .min()
+        })
+        .flatten();
+
+    let mut events: Vec<LogEvent> = Vec::new();
+    let mut window = FIRST_WINDOW_MS;
+    let mut searched_from = now.max(newest + 1);
+    let mut start_time = newest - window;
+    loop {
+        let query = LogQuery {
+            log_group_name: log_group_name.to_string(),
+            start_time,
+            end_time: searched_from,
+            filter_pattern: filter_pattern.clone(),
+        };
+        let narrowable = searched_from - start_time > MIN_WINDOW_MS;
+        let found_so_far = count(&events, limit);
+        let Some(mut older) = fetch_window(backend, &query, |older| {
+            narrowable && found_so_far + count(older, limit) >= target(limit)
+        })
+        .await?
+        else {
+            start_time += (searched_from - start_time) / 2;
+            continue;
+        };
+        older.append(&mut events);
+        events = older;
+        searched_from = start_time;
+
+        let exhausted = match oldest {
+            Some(oldest) => start_time <= oldest,
+            None => window >= MAX_WINDOW_MS,
+        };
+        if exhausted || count(&events, limit) >= target(limit) {
+            break;
+        }
+        window *= WINDOW_GROWTH;
+        start_time = newest - window;
+    }
+
+    events.sort_by_key(|event| event.timestamp);
+    let keep_from = match limit {
+        RecentLimit::Events(count) => events.len().saturating_sub(count),
+        RecentLimit::Invocations(count) => events
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, event)| is_invocation_start(&event.message))
+            .nth(count.saturating_sub(1))
+            .map_or(0, |(index, _)| index),
+    };
+    events.drain(..keep_from);
+
+    let (Some(first), Some(last)) = (events.first(), events.last()) else {
+        return Err(anyhow!("{} has no matching log events", log_group_name));
+    };
+    // Nothing was found after the last event up to now, so the range can end
+    // anywhere in between; at least a minute keeps the timeline readable
+    Ok(RecentLogs {
+        start_time: first.timestamp,
+        end_time: (last.timestamp + 1).max((first.timestamp + 60_000).min(now)),
+        events,
+    })
+}
+
+/// Pages through `query`, or gives up with `None` as soon as `enough`
+/// holds for the events so far while more pages remain.
+async fn fetch_window(
+    backend: &dyn LogBackend,
+    query: &LogQuery,
+    enough: impl Fn(&[LogEvent]) -> bool,
+) -> Result<Option<Vec<LogEvent>>> {
+    let mut logs = Vec::new();
+    let mut next_token = None;
+    loop {
+        let page = backend.filter_log_events(query, next_token.take()).await?;
+        logs.extend(page.events);
+        next_token = page.next_token;
+        if next_token.is_none() {
+            return Ok(Some(logs));
+        }
+        if enough(&logs) {
+            return Ok(None);
+        }
+    }
+}
+
+fn target(limit: RecentLimit) -> usize {
+    match limit {
+        RecentLimit::Events(count) | RecentLimit::Invocations(count) => count,
+    }
+}
+
+/// How much of `limit` the events make up.
+fn count(events: &[LogEvent], limit: RecentLimit) -> usize {
+    match limit {
+        RecentLimit::Events(_) => events.len(),
+        RecentLimit::Invocations(_) => events
+            .iter()
+            .filter(|event| is_invocation_start(&event.message))
+            .count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+
+    const GROUP: &str = "/aws/lambda/chatty";
+    const NOW: i64 = 1_800_000_000_000;
+
+    /// An event every 10ms over the last minute, with a `START` line every 50.
+    fn chatty() -> FakeBackend {
+        let events = (0..6_000)
+            .map(|i| LogEvent {
+                event_id: Some(i.to_string()),
+                log_stream_name: Some("stream".to_string()),
+                timestamp: NOW - 60_000 + i * 10,
+                ingestion_time: NOW - 60_000 + i * 10,
+                message: if i % 50 == 0 {
+                    format!("START RequestId: {} Version: $LATEST", i)
+                } else {
+                    format!("line {}", i)
+                },
+            })
+            .collect();
+        FakeBackend::new().with_events(GROUP, events)
+    }
+
+    #[tokio::test]
+    async fn finds_the_newest_events_in_a_few_pages() {
+        let backend = chatty();
+        let recent = find_recent_logs(&backend, GROUP, None, RecentLimit::Events(10), NOW)
+            .await
+            .unwrap();
+
+        let timestamps: Vec<i64> = recent.events.iter().map(|e| e.timestamp).collect();
+        let expected: Vec<i64> = (5_990..6_000).map(|i| NOW - 60_000 + i * 10).collect();
+        assert_eq!(timestamps, expected);
+        // Paging through the whole first minute would take 60 pages
+        assert!(
+            backend.filter_requests() < 15,
+            "{}",
+            backend.filter_requests()
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_invocations_by_start_lines() {
+        let backend = chatty();
+        let recent = find_recent_logs(&backend, GROUP, None, RecentLimit::Invocations(3), NOW)
+            .await
+            .unwrap();
+
+        let starts = recent
+            .events
+            .iter()
+            .filter(|e| is_invocation_start(&e.message))
+            .count();
+        assert_eq!(starts, 3);
+        assert!(recent.events[0]
+            .message
+            .starts_with("START RequestId: 5850"));
+        assert_eq!(recent.events.len(), 150);
+    }
+
+    #[test]
+    fn parses_limits() {
+        let cases = [
+            ("recent", Some(RecentLimit::Events(DEFAULT_RECENT_EVENTS))),
+            ("recent 20", Some(RecentLimit::Events(20))),
+            ("Recent 20 events", Some(RecentLimit::Events(20))),
+            ("recent 5 invocations", Some(RecentLimit::Invocations(5))),
+            ("recently", None),
+            ("last 2h", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                RecentLimit::parse(input).map(|r| r.unwrap()),
+                expected,
+                "{}",
+                input
+            );
+        }
+        for input in ["recent 0", "recent x", "recent 5 days"] {
+            assert!(RecentLimit::parse(input).unwrap().is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::backend::LogEvent;
+use crate::invocations::{is_error_line, line_problems};
+
+/// How many buckets the viewer's range is split into.
+pub const TIMELINE_BUCKETS: usize = 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Bucket {
+    pub events: usize,
+    /// Error lines and failed invocation markers, a subset of `events`.
+    pub errors: usize,
+}
+
+/// Event counts over a time range, split into equally long buckets.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub start: i64,
+    pub end: i64,
+    pub buckets: Vec<Bucket>,
+}
+
+impl Histogram {
+    /// Counts `events` between `start` and `end` (epoch millis); events
+    /// outside the range are left out.
+    pub fn new(events: &[LogEvent], start: i64, end: i64, bucket_count: usize) -> Self {
+        let mut histogram = Self {
+            start,
+            end: end.max(start + 1),
+            buckets: vec![Bucket::default(); bucket_count.max(1)],
+        };
+        for event in events {
+            let Some(i) = histogram.bucket_of(event.timestamp) else {
+                continue;
+            };
+            let bucket = &mut histogram.buckets[i];
+            bucket.events += 1;
+            if is_error_line(&event.message) || line_problems(&event.message).is_failure() {
+                bucket.errors += 1;
+            }
+        }
+        histogram
+    }
+
+    pub fn bucket_of(&self, timestamp: i64) -> Option<usize> {
+        if timestamp < self.start || timestamp > self.end {
+            return None;
+        }
+        let offset = (timestamp - self.start) as i128 * self.buckets.len() as i128;
+        let i = (offset / (self.end - self.start) as i128) as usize;
+        // The end of the range belongs to the last bucket
+        Some(i.min(self.buckets.len() - 1))
+    }
+
+    /// Start and end (epoch millis) of a bucket: the first timestamp
+    /// `bucket_of` puts in it and the first one of the next bucket.
+    pub fn bucket_range(&self, i: usize) -> (i64, i64) {
+        let span = (self.end - self.start) as i128;
+        let count = self.buckets.len() as i128;
+        let at = |i: usize| self.start + ((span * i as i128 + count - 1) / count) as i64;
+        (at(i), at(i + 1))
+    }
+
+    /// Length of one bucket in milliseconds.
+    pub fn bucket_millis(&self) -> i64 {
+        (self.end - self.start) / self.buckets.len() as i64
+    }
+
+    pub fn max_events(&self) -> usize {
+        self.buckets.iter().map(|b| b.events).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puts_timestamps_in_buckets() {
+        let histogram = Histogram::new(&[], 1_000, 2_000, 10);
+        let cases = [
+            (999, None),
+            (1_000, Some(0)),
+            (1_099, Some(0)),
+            (1_100, Some(1)),
+            (1_999, Some(9)),
+            // The end of the range belongs to the last bucket
+            (2_000, Some(9)),
+            (2_001, None),
+        ];
+        for (timestamp, bucket) in cases {
+            assert_eq!(histogram.bucket_of(timestamp), bucket, "{}", timestamp);
+        }
+        assert_eq!(histogram.bucket_range(0), (1_000, 1_100));
+        assert_eq!(histogram.bucket_range(9), (1_900, 2_000));
+        assert_eq!(histogram.bucket_millis(), 100);
+    }
+
+    #[test]
+    fn bucket_ranges_agree_with_bucket_of() {
+        // Buckets of a third of the range don't end on whole millis
+        let histogram = Histogram::new(&[], 0, 1_000, 3);
+        assert_eq!(histogram.bucket_range(0), (0, 334));
+        assert_eq!(histogram.bucket_range(1), (334, 667));
+        assert_eq!(histogram.bucket_range(2), (667, 1_000));
+        for i in 0..3 {
+            let (start, end) = histogram.bucket_range(i);
+            assert_eq!(histogram.bucket_of(start), Some(i));
+            assert_eq!(histogram.bucket_of(end - 1), Some(i));
+        }
+    }
+
+    #[test]
+    fn zero_length_ranges_get_a_millisecond() {
+        let histogram = Histogram::new(&[], 5_000, 5_000, 60);
+        assert_eq!(histogram.end, 5_001);
+        assert_eq!(histogram.bucket_of(5_000), Some(0));
+        assert_eq!(histogram.bucket_of(5_001), Some(59));
+        assert_eq!(histogram.bucket_range(0), (5_000, 5_001));
+        assert_eq!(histogram.bucket_range(59), (5_001, 5_001));
+    }
+}
@@ -1,11 +1,108 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::Deserialize;
 use std::fs;
-use toml;
+use std::path::PathBuf;
+
+use crate::recent::RecentLimit;
+use crate::utils::time_utils::{check_time_range, DisplayTimezone};
 
 #[derive(Debug, Deserialize)]
 pub struct AwsConfig {
+    #[serde(default)]
     pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub logs: LogSettings,
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+}
+
+/// The `[logs]` section of the app config.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LogSettings {
+    /// Pages fetched in the background when the viewer opens; further pages
+    /// are only fetched when scrolling towards the end.
+    pub background_pages: usize,
+    /// Minutes shown before and after an event with Ctrl+N.
+    pub around_minutes: i64,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            background_pages: 10,
+            around_minutes: 5,
+        }
+    }
+}
+
+/// The `[cache]` section of the app config.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CacheSettings {
+    /// Minutes a cached function list is used before it's refreshed in the
+    /// background.
+    pub function_ttl_minutes: i64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            function_ttl_minutes: 60,
+        }
+    }
+}
+
+/// The `[display]` section of the app config.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct DisplaySettings {
+    /// `local`, `utc` or an IANA name such as `Europe/Berlin`; Ctrl+U
+    /// switches between local time, UTC and this one.
+    pub timezone: DisplayTimezone,
+    pub quick_ranges: Vec<QuickRange>,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            timezone: DisplayTimezone::Local,
+            quick_ranges: [
+                ("Last Hour", "1h"),
+                ("Last 2 Hours", "2h"),
+                ("Last 3 Hours", "3h"),
+                ("Last 6 Hours", "6h"),
+                ("Last 12 Hours", "12h"),
+                ("Last 24 Hours", "24h"),
+                ("Last 3 Days", "3d"),
+                ("Last Week", "7d"),
+                ("Most Recent Logs", "recent"),
+            ]
+            .into_iter()
+            .map(|(name, range)| QuickRange {
+                name: Some(name.to_string()),
+                range: range.to_string(),
+            })
+            .collect(),
+        }
+    }
+}
+
+/// An entry of the time range screen's quick range list.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct QuickRange {
+    pub name: Option<String>,
+    /// A range expression, e.g. `15m`, `today` or `yesterday 09:00..17:00`,
+    /// or `recent 100` / `recent 20 invocations` for the latest events.
+    pub range: String,
+}
+
+impl QuickRange {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.range)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -14,15 +111,61 @@ pub struct Profile {
     pub region: String,
 }
 
-pub fn read_aws_profiles() -> Result<Vec<Profile>> {
-    let config_path = "config.toml";
-
-    if !std::path::Path::new(config_path).exists() {
-        return Ok(Vec::new());
+/// The app's own config: `./config.toml` if present, otherwise
+/// `<config dir>/aws-logs-viewer/config.toml`.
+pub fn app_config_path() -> Option<PathBuf> {
+    let local = PathBuf::from("config.toml");
+    if local.exists() {
+        return Some(local);
     }
 
+    let path = dirs::config_dir()?
+        .join("aws-logs-viewer")
+        .join("config.toml");
+    path.exists().then_some(path)
+}
+
+fn read_app_config() -> Result<Option<AwsConfig>> {
+    let Some(config_path) = app_config_path() else {
+        return Ok(None);
+    };
+
     let content = fs::read_to_string(config_path)?;
-    let config: AwsConfig = toml::from_str(&content)?;
+    Ok(Some(toml::from_str(&content)?))
+}
 
-    Ok(config.profiles)
+pub fn read_aws_profiles() -> Result<Vec<Profile>> {
+    Ok(read_app_config()?
+        .map(|config| config.profiles)
+        .unwrap_or_default())
+}
+
+pub fn read_log_settings() -> Result<LogSettings> {
+    Ok(read_app_config()?
+        .map(|config| config.logs)
+        .unwrap_or_default())
+}
+
+pub fn read_cache_settings() -> Result<CacheSettings> {
+    Ok(read_app_config()?
+        .map(|config| config.cache)
+        .unwrap_or_default())
+}
+
+pub fn read_display_settings() -> Result<DisplaySettings> {
+    let settings = read_app_config()?
+        .map(|config| config.display)
+        .unwrap_or_default();
+    if settings.quick_ranges.is_empty() {
+        return Err(anyhow!("[display] quick_ranges must not be empty"));
+    }
+    for quick_range in &settings.quick_ranges {
+        // Only the syntax; presets like `today 09:00..now` are resolved when picked
+        match RecentLimit::parse(&quick_range.range) {
+            Some(limit) => limit.map(|_| ()),
+            None => check_time_range(&quick_range.range),
+        }
+        .map_err(|e| anyhow!("quick range '{}': {}", quick_range.display_name(), e))?;
+    }
+    Ok(settings)
 }
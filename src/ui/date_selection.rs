@@ -1,14 +1,13 @@
-use crate::app_state::{date_selection::DateSelection, FocusedPanel};
+use crate::app_state::date_selection::DateSelection;
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
 use crate::app_state::date_selection::{ActiveColumn, DateField};
-use chrono::{DateTime, Local};
 
 pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection) {
     // Main layout with outer margin
@@ -17,15 +16,17 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
         .constraints([
             Constraint::Length(3), // Title
             Constraint::Min(0),    // Content
-            Constraint::Length(3), // Helper text
+            Constraint::Length(4), // Helper text
         ])
         .margin(1)
-        .split(f.size());
+        .split(f.area());
 
     // Title bar at the top
     let title = Paragraph::new(format!(
-        "Log Viewer | Profile: {} | Function: {}",
-        date_selection.profile_name, date_selection.function_name
+        "Log Viewer | Profile: {} | {} | Time: {}",
+        date_selection.profile_name,
+        date_selection.source.label(),
+        date_selection.timezone.label()
     ))
     .style(Style::default().fg(Color::Cyan))
     .block(Block::default().borders(Borders::ALL))
@@ -99,7 +100,10 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
             Constraint::Length(2), // Spacing
             Constraint::Length(1), // To label
             Constraint::Length(3), // To input
-            Constraint::Min(0),    // Remaining space
+            Constraint::Length(2), // Spacing
+            Constraint::Length(1), // Expression label
+            Constraint::Length(3), // Expression input
+            Constraint::Min(0),    // Expression error
         ])
         .margin(1)
         .split(custom_range_area);
@@ -116,7 +120,9 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     f.render_widget(from_label, date_fields[0]);
 
     let from_text = format_date_with_highlight(
-        date_selection.from_date,
+        date_selection
+            .timezone
+            .format(&date_selection.from_date, "%Y-%m-%d %H:%M"),
         date_selection.is_selecting_from
             && date_selection.active_column == ActiveColumn::CustomRange,
         &date_selection.current_field,
@@ -142,7 +148,9 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     f.render_widget(to_label, date_fields[3]);
 
     let to_text = format_date_with_highlight(
-        date_selection.to_date,
+        date_selection
+            .timezone
+            .format(&date_selection.to_date, "%Y-%m-%d %H:%M"),
         !date_selection.is_selecting_from
             && date_selection.active_column == ActiveColumn::CustomRange,
         &date_selection.current_field,
@@ -156,16 +164,53 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
         .alignment(Alignment::Left);
     f.render_widget(to_input, date_fields[4]);
 
+    // Range expression, typed after `/`
+    let expression_style = if date_selection.range_input.is_some() {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let expression_label = Paragraph::new("Expression (/)").style(expression_style);
+    f.render_widget(expression_label, date_fields[6]);
+
+    let expression_text = match (&date_selection.range_input, date_selection.recent) {
+        (Some(input), _) => Line::from(format!("{}_", input)),
+        (None, Some(limit)) => Line::styled(
+            format!("The {}, however old", limit.describe()),
+            Style::default().fg(Color::Cyan),
+        ),
+        (None, None) => Line::styled(
+            "e.g. 15m, 2h ago..now, yesterday 09:00..10:30, 2026-10-15T14:00Z+30m, recent 20 invocations",
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    let expression_input = Paragraph::new(expression_text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(expression_style),
+    );
+    f.render_widget(expression_input, date_fields[7]);
+
+    if let Some(error) = &date_selection.range_error {
+        let error_text = Paragraph::new(error.as_str())
+            .style(Style::default().fg(Color::Red))
+            .wrap(Wrap { trim: true });
+        f.render_widget(error_text, date_fields[8]);
+    }
+
     // Helper text at the bottom with border
     let help_text = match date_selection.active_column {
+        _ if date_selection.range_input.is_some() => {
+            "Type a range expression | Enter: View logs | Esc: Cancel"
+        }
         ActiveColumn::QuickRanges => {
-            "1/2: Switch Columns | ↑↓: Select Range | Enter: Confirm | Esc: Back | q: Quit"
+            "1/2: Switch Columns | ↑↓: Select Range | /: Type a range | r: Most recent logs | Enter: View logs | i: Insights query | s: Streams | Ctrl+U: Timezone | Esc: Back | q: Quit"
         }
         ActiveColumn::CustomRange => {
             if date_selection.is_selecting_from {
-                "1/2: Switch Columns | Tab: Switch to To | ←→: Select Field | ↑↓: Adjust Value | Enter: Confirm | Esc: Back | q: Quit"
+                "1/2: Switch Columns | Tab: To | ←→: Field | ↑↓: Adjust Value | Enter: View logs | r: Most recent logs | i: Insights query | s: Streams | Ctrl+U: Timezone | Esc: Back | q: Quit"
             } else {
-                "1/2: Switch Columns | Tab: Switch to From | ←→: Select Field | ↑↓: Adjust Value | Enter: Confirm | Esc: Back | q: Quit"
+                "1/2: Switch Columns | Tab: From | ←→: Field | ↑↓: Adjust Value | Enter: View logs | r: Most recent logs | i: Insights query | s: Streams | Ctrl+U: Timezone | Esc: Back | q: Quit"
             }
         }
     };
@@ -173,17 +218,18 @@ pub fn draw_date_selection_panel(f: &mut Frame, date_selection: &DateSelection)
     let help = Paragraph::new(help_text)
         .style(Style::default().fg(Color::Green))
         .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
         .block(Block::default().borders(Borders::ALL));
 
     f.render_widget(help, main_area[2]);
 }
 
+/// `date_str` is formatted as `%Y-%m-%d %H:%M`.
 fn format_date_with_highlight(
-    date: DateTime<Local>,
+    date_str: String,
     is_selected: bool,
     current_field: &DateField,
 ) -> Text<'static> {
-    let date_str = date.format("%Y-%m-%d %H:%M").to_string();
     let mut spans = Vec::new();
 
     if !is_selected {
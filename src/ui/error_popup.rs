@@ -0,0 +1,45 @@
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app_state::error_popup::ErrorPopup;
+use crate::utils::ui_utils::centered_rect;
+
+pub fn draw_error_popup(f: &mut Frame, popup: &ErrorPopup) {
+    let area = centered_rect(80, 12, f.area());
+
+    let controls = if popup.can_retry() {
+        "r: Retry | Esc: Back | Enter: Dismiss | q: Quit"
+    } else {
+        "Esc: Back | Enter: Dismiss | q: Quit"
+    };
+
+    let text = vec![
+        Line::from(Span::styled(
+            format!("{} failed", popup.context),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(popup.error.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(
+            popup.error.hint(),
+            Style::default().fg(Color::Yellow),
+        )),
+    ];
+
+    let content = Paragraph::new(text).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .title(popup.error.title())
+            .title_bottom(Line::from(controls).alignment(Alignment::Center))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(content, area);
+}
@@ -1,13 +1,26 @@
+use chrono::Utc;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
-use crate::app_state::function_selection::FunctionSelection;
+use crate::app_state::function_selection::{FunctionRow, FunctionSelection, GroupBy};
+use crate::backend::FunctionInfo;
+use crate::fuzzy::FuzzyMatch;
+use crate::utils::time_utils::DisplayTimezone;
+use crate::utils::ui_utils::{format_age, format_bytes};
 
-pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
+/// Narrower terminals only get the list.
+const MIN_WIDTH_FOR_DETAILS: u16 = 100;
+
+pub fn draw_function_selection(
+    f: &mut Frame,
+    state: &mut FunctionSelection,
+    timezone: DisplayTimezone,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -16,7 +29,7 @@ pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
             Constraint::Min(0),    // Main content
             Constraint::Length(3), // Controls
         ])
-        .split(f.size());
+        .split(f.area());
 
     // Title
     let title_text = format!(
@@ -39,69 +52,177 @@ pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
         .block(Block::default().title("Filter").borders(Borders::ALL));
     f.render_widget(filter_input, inner_chunks[0]);
 
+    // The list, with the details of the selected function beside it
+    let list_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(if chunks[1].width >= MIN_WIDTH_FOR_DETAILS {
+            vec![Constraint::Min(0), Constraint::Length(46)]
+        } else {
+            vec![Constraint::Min(0)]
+        })
+        .split(inner_chunks[1]);
+
     // Functions list with scroll state
-    let total_functions = state.filtered_functions.len();
-    let items_per_page = inner_chunks[1].height as usize - 2; // Subtract 2 for borders
-
-    // Calculate scroll position
-    let selected_index = state.list_state.selected().unwrap_or(0);
-    let scroll_threshold = items_per_page / 2;
-    let scroll_offset = if selected_index > scroll_threshold {
-        selected_index - scroll_threshold
+    let total_rows = state.rows.len();
+    let grouped = state.group_by != GroupBy::None;
+    // Functions under a group or pinned section header are indented
+    let indent = if state
+        .rows
+        .iter()
+        .any(|row| matches!(row, FunctionRow::Group { .. }))
+    {
+        "  "
     } else {
-        0
+        ""
     };
+    // Subtract the borders, and the header when there are columns
+    let items_per_page =
+        (list_chunks[0].height as usize).saturating_sub(if state.show_columns { 3 } else { 2 });
+    let selected_index = state.selected_index.min(total_rows.saturating_sub(1));
 
-    // Create visible items
-    let visible_items: Vec<ListItem> = state
-        .filtered_functions
+    let rows: Vec<Row> = state
+        .rows
         .iter()
-        .skip(scroll_offset)
-        .take(items_per_page)
-        .enumerate()
-        .map(|(i, name)| {
-            let display_text = if name.len() > inner_chunks[1].width as usize - 4 {
-                format!("{}...", &name[..inner_chunks[1].width as usize - 7])
-            } else {
-                name.clone()
+        .map(|row| {
+            let function = match row {
+                FunctionRow::Group {
+                    name,
+                    count,
+                    collapsed,
+                } => {
+                    return Row::new([format!(
+                        "{} {} ({})",
+                        if *collapsed { "▸" } else { "▾" },
+                        name,
+                        count
+                    )])
+                    .style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                FunctionRow::Function(index) | FunctionRow::Pinned(index) => {
+                    &state.filtered_functions[*index]
+                }
             };
-
-            let style = if i + scroll_offset == selected_index {
-                Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            let mut name =
+                highlighted_name(&function.name, state.matches.get(&function.name), indent);
+            if state.history.is_favorite(&function.name) {
+                name.push_span(Span::styled(" ★", Style::default().fg(Color::Yellow)));
+            }
+            if state.show_columns {
+                Row::new([
+                    Cell::from(name),
+                    Cell::from(function.runtime.clone().unwrap_or_else(|| "-".to_string())),
+                    Cell::from(Line::from(memory(function)).right_aligned()),
+                    Cell::from(Line::from(timeout(function)).right_aligned()),
+                    Cell::from(function.architecture()),
+                ])
             } else {
-                Style::default()
-            };
-
-            ListItem::new(display_text).style(style)
+                Row::new([Cell::from(name)])
+            }
         })
         .collect();
 
     // Create scroll indicator
-    let scroll_indicator = if total_functions > items_per_page {
-        let progress =
-            (scroll_offset as f64 / (total_functions - items_per_page) as f64 * 100.0) as u16;
+    let scroll_indicator = if total_rows > items_per_page {
+        let progress = (selected_index as f64 / (total_rows - 1) as f64 * 100.0) as u16;
+        format!(" ({}/{}) {}%", selected_index + 1, total_rows, progress)
+    } else {
+        format!(" ({}/{})", selected_index + 1, total_rows)
+    };
+    let arrangement = if grouped {
         format!(
-            " ({}/{}) {}%",
-            selected_index + 1,
-            total_functions,
-            progress
+            " | {} functions | Sort: {} | Group: {}",
+            state.filtered_functions.len(),
+            state.sort_by.display_name(),
+            state.group_by.display_name()
         )
     } else {
-        format!(" ({}/{})", selected_index + 1, total_functions)
+        format!(" | Sort: {}", state.sort_by.display_name())
+    };
+    let status = match (state.loading(), state.fetched_at) {
+        (Some(loading), _) => Span::styled(
+            format!(" {} ", loading.summary()),
+            Style::default().fg(Color::Yellow),
+        ),
+        // Only outdated when the refresh failed
+        (None, Some(fetched_at)) => Span::styled(
+            format!(
+                " Cached {} ",
+                format_age(fetched_at, Utc::now().timestamp_millis())
+            ),
+            Style::default().fg(if state.is_stale() {
+                Color::Yellow
+            } else {
+                Color::DarkGray
+            }),
+        ),
+        (None, None) => Span::raw(""),
+    };
+
+    let widths = if state.show_columns {
+        vec![
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(7),
+        ]
+    } else {
+        vec![Constraint::Fill(1)]
     };
+    let mut functions_table = Table::new(rows, widths)
+        .column_spacing(2)
+        .block(
+            Block::default()
+                .title(format!(
+                    "Lambda Functions{}{}",
+                    scroll_indicator, arrangement
+                ))
+                .title(Line::from(status).right_aligned())
+                .borders(Borders::ALL),
+        )
+        .row_highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+    if state.show_columns {
+        functions_table = functions_table.header(
+            Row::new(["Name", "Runtime", "Memory", "Timeout", "Arch"]).style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+    f.render_stateful_widget(functions_table, list_chunks[0], &mut state.table_state);
 
-    let functions_list = List::new(visible_items).block(
-        Block::default()
-            .title(format!("Lambda Functions{}", scroll_indicator))
-            .borders(Borders::ALL),
-    ); // Removed highlight_style
-    f.render_stateful_widget(functions_list, inner_chunks[1], &mut state.list_state);
+    if let Some(&details_area) = list_chunks.get(1) {
+        let details = Paragraph::new(match state.rows.get(state.selected_index) {
+            Some(FunctionRow::Function(index) | FunctionRow::Pinned(index)) => {
+                details_lines(state, &state.filtered_functions[*index], timezone)
+            }
+            Some(FunctionRow::Group { name, count, .. }) => vec![
+                Line::from(Span::styled(
+                    name.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(format!("{} functions", count)),
+            ],
+            None => Vec::new(),
+        })
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title("Details").borders(Borders::ALL));
+        f.render_widget(details, details_area);
+    }
 
     // Controls
-    let controls = if total_functions > items_per_page {
-        "↑↓: Navigate | PgUp/PgDn: Scroll | Enter: Select | Esc: Back to profiles | q: Quit"
+    let controls = if !indent.is_empty() {
+        "↑↓: Navigate | Enter: Select/Fold | ←→: Fold | Ctrl+F: Favorite | Ctrl+R: Refresh | Ctrl+S: Sort | Ctrl+G: Group | Ctrl+D: Columns | Tab: Log groups | Esc: Back | q: Quit"
+    } else if total_rows > items_per_page {
+        "↑↓: Navigate | PgUp/PgDn: Scroll | Enter: Select | Ctrl+F: Favorite | Ctrl+R: Refresh | Ctrl+S: Sort | Ctrl+G: Group | Ctrl+D: Columns | Tab: Log groups | Esc: Back | q: Quit"
     } else {
-        "↑↓: Navigate | Enter: Select | Esc: Back to profiles | q: Quit"
+        "↑↓: Navigate | Enter: Select | Ctrl+F: Favorite | Ctrl+R: Refresh | Ctrl+S: Sort | Ctrl+G: Group | Ctrl+D: Columns | Tab: Log groups | Esc: Back | q: Quit"
     };
 
     let controls_widget = Paragraph::new(controls)
@@ -109,3 +230,125 @@ pub fn draw_function_selection(f: &mut Frame, state: &mut FunctionSelection) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(controls_widget, chunks[2]);
 }
+
+/// The name with the characters matched by the filter highlighted.
+fn highlighted_name(name: &str, found: Option<&FuzzyMatch>, indent: &str) -> Line<'static> {
+    let mut spans = vec![Span::raw(indent.to_string())];
+    let positions = found.map_or(&[][..], |found| &found.positions[..]);
+    let mut chars = name.chars().enumerate().peekable();
+
+    // Runs of matched and unmatched characters
+    while let Some((index, c)) = chars.next() {
+        let matched = positions.binary_search(&index).is_ok();
+        let mut run = c.to_string();
+        while let Some((next, c)) = chars.peek() {
+            if positions.binary_search(next).is_ok() != matched {
+                break;
+            }
+            run.push(*c);
+            chars.next();
+        }
+        spans.push(if matched {
+            Span::styled(
+                run,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )
+        } else {
+            Span::raw(run)
+        });
+    }
+    Line::from(spans)
+}
+
+fn memory(function: &FunctionInfo) -> String {
+    function
+        .memory_mb
+        .map_or("-".to_string(), |mb| format!("{} MB", mb))
+}
+
+fn timeout(function: &FunctionInfo) -> String {
+    function
+        .timeout_secs
+        .map_or("-".to_string(), |secs| format!("{}s", secs))
+}
+
+fn details_lines(
+    state: &FunctionSelection,
+    function: &FunctionInfo,
+    timezone: DisplayTimezone,
+) -> Vec<Line<'static>> {
+    let label =
+        |name: &str| Span::styled(format!("{:<14}", name), Style::default().fg(Color::Cyan));
+    let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    let now = Utc::now().timestamp_millis();
+    let with_age = |millis: i64| {
+        format!(
+            "{} ({})",
+            timezone.format_millis(millis, "%Y-%m-%d %H:%M"),
+            format_age(millis, now)
+        )
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            function.name.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            label("Runtime"),
+            Span::raw(or_dash(&function.runtime)),
+        ]),
+        Line::from(vec![
+            label("Handler"),
+            Span::raw(or_dash(&function.handler)),
+        ]),
+        Line::from(vec![label("Memory"), Span::raw(memory(function))]),
+        Line::from(vec![label("Timeout"), Span::raw(timeout(function))]),
+        Line::from(vec![
+            label("Architecture"),
+            Span::raw(function.architecture()),
+        ]),
+        Line::from(vec![
+            label("Code size"),
+            Span::raw(
+                function
+                    .code_size
+                    .map_or("-".to_string(), |bytes| format_bytes(bytes as f64)),
+            ),
+        ]),
+        Line::from(vec![
+            label("Last modified"),
+            Span::raw(function.last_modified.map_or("-".to_string(), with_age)),
+        ]),
+    ];
+    if let Some(last_logged) = &state.last_logged {
+        lines.push(Line::from(vec![
+            label("Last logged"),
+            Span::raw(
+                last_logged
+                    .get(&function.name)
+                    .map_or("never".to_string(), |millis| with_age(*millis)),
+            ),
+        ]));
+    }
+    if let Some(tags) = state
+        .tags
+        .as_ref()
+        .and_then(|tags| tags.get(&function.name))
+    {
+        lines.push(Line::from(""));
+        lines.extend(
+            tags.iter()
+                .map(|(key, value)| Line::from(vec![label(key), Span::raw(value.clone())])),
+        );
+    }
+    if let Some(description) = &function.description {
+        lines.push(Line::from(""));
+        lines.push(Line::from(description.clone()));
+    }
+    lines
+}
@@ -0,0 +1,163 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::app_state::{date_selection::DateSelection, insights::InsightsView};
+use crate::utils::ui_utils::format_bytes;
+
+const MAX_COLUMN_WIDTH: usize = 40;
+
+pub fn draw_insights_view(f: &mut Frame, date_selection: &DateSelection, insights: &InsightsView) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Query
+            Constraint::Min(0),    // Results
+            Constraint::Length(3), // Controls
+        ])
+        .margin(1)
+        .split(f.area());
+
+    let title = Paragraph::new(format!(
+        "Logs Insights | Profile: {} | {} | {} - {}",
+        date_selection.profile_name,
+        insights.source.label(),
+        date_selection
+            .timezone
+            .format(&insights.from_date, "%Y-%m-%d %H:%M %Z"),
+        date_selection
+            .timezone
+            .format(&insights.to_date, "%Y-%m-%d %H:%M %Z")
+    ))
+    .style(Style::default().fg(Color::Cyan))
+    .block(Block::default().borders(Borders::ALL))
+    .alignment(Alignment::Center);
+    f.render_widget(title, layout[0]);
+
+    // Query input
+    let (query_text, query_style) = if insights.editing {
+        (
+            format!("{}_", insights.query_input),
+            Style::default().fg(Color::Yellow),
+        )
+    } else {
+        (insights.query_input.clone(), Style::default())
+    };
+    let query = Paragraph::new(query_text).block(
+        Block::default()
+            .title("Query")
+            .borders(Borders::ALL)
+            .border_style(query_style),
+    );
+    f.render_widget(query, layout[1]);
+
+    draw_results_table(f, insights, layout[2]);
+
+    // Controls
+    let controls = if insights.editing {
+        "Type a Logs Insights query | Enter: Run | Esc: Back"
+    } else if insights.loading.is_some() {
+        "↑↓: Navigate | ←→: Sort column | s: Sort order | Esc: Cancel query | q: Quit"
+    } else {
+        "↑↓: Navigate | ←→: Sort column | s: Sort order | e: Edit query | Enter: Run again | Esc: Back | q: Quit"
+    };
+    let controls = Paragraph::new(controls)
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, layout[3]);
+}
+
+fn draw_results_table(f: &mut Frame, insights: &InsightsView, area: ratatui::layout::Rect) {
+    let status = if let Some(loading) = &insights.loading {
+        Span::styled(
+            format!(" {} ", loading.summary()),
+            Style::default().fg(Color::Yellow),
+        )
+    } else if let Some(results) = &insights.results {
+        let statistics = &results.statistics;
+        Span::styled(
+            format!(
+                " {} | {} matched | {} records scanned | {} scanned ",
+                results.status.display_name(),
+                statistics.records_matched,
+                statistics.records_scanned,
+                format_bytes(statistics.bytes_scanned)
+            ),
+            Style::default().fg(Color::Green),
+        )
+    } else {
+        Span::raw("")
+    };
+
+    let block = Block::default()
+        .title(format!("Results ({})", insights.row_count()))
+        .title(Line::from(status).right_aligned())
+        .borders(Borders::ALL);
+
+    if insights.columns.is_empty() {
+        let placeholder = if insights.results.is_some() {
+            "No results"
+        } else {
+            "Press Enter to run the query"
+        };
+        let placeholder = Paragraph::new(placeholder)
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(placeholder, area);
+        return;
+    }
+
+    let header = Row::new(insights.columns.iter().enumerate().map(|(i, column)| {
+        let arrow = match (insights.sort_column == Some(i), insights.sort_descending) {
+            (true, true) => " ▼",
+            (true, false) => " ▲",
+            (false, _) => "",
+        };
+        Cell::from(format!("{}{}", column, arrow))
+    }))
+    .style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows: Vec<Row> = (0..insights.row_count())
+        .map(|row| {
+            Row::new((0..insights.columns.len()).map(|column| {
+                // Keep every row on a single line
+                Cell::from(insights.cell(row, column).replace(['\n', '\t'], " "))
+            }))
+        })
+        .collect();
+
+    // Size columns to their content; the last one takes the remaining width
+    let widths: Vec<Constraint> = (0..insights.columns.len())
+        .map(|column| {
+            if column + 1 == insights.columns.len() {
+                return Constraint::Fill(1);
+            }
+            let content_width = (0..insights.row_count())
+                .map(|row| insights.cell(row, column).chars().count())
+                .chain(std::iter::once(
+                    insights.columns[column].chars().count() + 2,
+                ))
+                .max()
+                .unwrap_or(0);
+            Constraint::Length(content_width.min(MAX_COLUMN_WIDTH) as u16)
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+
+    let mut state = TableState::default().with_selected(Some(insights.selected_row));
+    f.render_stateful_widget(table, area, &mut state);
+}
@@ -0,0 +1,127 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::app_state::log_group_selection::LogGroupSelection;
+use crate::utils::ui_utils::format_bytes;
+
+pub fn draw_log_group_selection(f: &mut Frame, state: &LogGroupSelection) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Filter and prefix
+            Constraint::Min(0),    // Log groups
+            Constraint::Length(3), // Controls
+        ])
+        .split(f.area());
+
+    // Title
+    let title = Paragraph::new(format!(
+        "CloudWatch Log Groups | Profile: {} | Region: {}",
+        state.profile.name, state.profile.region
+    ))
+    .style(Style::default().fg(Color::Cyan))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    // Local filter and the server side name prefix
+    let filter_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    let filter_input = Paragraph::new(state.filter_input.as_str())
+        .block(Block::default().title("Filter").borders(Borders::ALL));
+    f.render_widget(filter_input, filter_chunks[0]);
+
+    let (prefix, prefix_style) = match &state.prefix_input {
+        Some(input) => (format!("{}_", input), Style::default().fg(Color::Yellow)),
+        None => (state.prefix.clone(), Style::default()),
+    };
+    let prefix_input = Paragraph::new(prefix).block(
+        Block::default()
+            .title("Name prefix (Ctrl+S)")
+            .borders(Borders::ALL)
+            .border_style(prefix_style),
+    );
+    f.render_widget(prefix_input, filter_chunks[1]);
+
+    // Log groups
+    let status = match &state.loading {
+        Some(loading) => Span::styled(
+            format!(" {} ", loading.summary()),
+            Style::default().fg(Color::Yellow),
+        ),
+        None => Span::raw(""),
+    };
+
+    let header = Row::new(["Name", "Stored", "Retention"]).style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = state
+        .filtered_log_groups
+        .iter()
+        .map(|group| {
+            Row::new([
+                Cell::from(group.name.clone()),
+                Cell::from(
+                    Line::from(
+                        group
+                            .stored_bytes
+                            .map_or("-".to_string(), |bytes| format_bytes(bytes as f64)),
+                    )
+                    .right_aligned(),
+                ),
+                Cell::from(
+                    group
+                        .retention_days
+                        .map_or("Never expire".to_string(), |days| format!("{} days", days)),
+                ),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(14),
+        ],
+    )
+    .header(header)
+    .column_spacing(2)
+    .block(
+        Block::default()
+            .title(format!(
+                "Log Groups ({}/{})",
+                (state.selected_index + 1).min(state.filtered_log_groups.len()),
+                state.filtered_log_groups.len()
+            ))
+            .title(Line::from(status).right_aligned())
+            .borders(Borders::ALL),
+    )
+    .row_highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+
+    let mut table_state = TableState::default().with_selected(Some(state.selected_index));
+    f.render_stateful_widget(table, chunks[2], &mut table_state);
+
+    // Controls
+    let controls = if state.prefix_input.is_some() {
+        "Type a log group name prefix | Enter: Apply | Esc: Cancel"
+    } else {
+        "↑↓: Navigate | Enter: Select | Ctrl+S: Name prefix | Tab: Lambda functions | Esc: Back | q: Quit"
+    };
+    let controls = Paragraph::new(controls)
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, chunks[3]);
+}
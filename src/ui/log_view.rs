@@ -1,19 +1,23 @@
 use crate::{
     app_state::{
-        date_selection::{DateField, DateSelection},
-        log_viewer::LogViewer,
+        date_selection::DateSelection,
+        invocation_list::{InvocationList, InvocationRow},
+        log_viewer::{LogViewer, ProblemFilter},
         FocusedPanel,
     },
-    utils::ui_utils::format_json,
+    export::ExportFormat,
+    invocations::{line_problems, Invocation, InvocationStats, InvocationStatus, Problems},
+    ui::timeline_view::draw_timeline,
+    utils::time_utils::DisplayTimezone,
+    utils::ui_utils::{centered_rect, format_json, short_stream_name},
 };
-use chrono::{DateTime, Local};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{
         Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
-        ScrollbarState,
+        ScrollbarState, Wrap,
     },
     Frame,
 };
@@ -22,7 +26,6 @@ pub fn draw_log_view(
     f: &mut Frame,
     date_selection: &DateSelection,
     log_viewer: Option<&LogViewer>,
-    is_loading: bool,
     focused_panel: FocusedPanel,
 ) {
     // Title bar at the top
@@ -33,10 +36,13 @@ pub fn draw_log_view(
             Constraint::Min(0),    // Rest of content
         ])
         .margin(1)
-        .split(f.size());
+        .split(f.area());
 
+    let stream = log_viewer
+        .and_then(LogViewer::stream_name)
+        .map_or(String::new(), |name| format!(" | Stream: {}", name));
     let title = Paragraph::new(format!(
-        "Step {}: {} | Profile: {} | Function: {}",
+        "Step {}: {} | Profile: {} | {}{} | Time: {}",
         if log_viewer.is_some() { "2" } else { "1" },
         if log_viewer.is_some() {
             "Log Viewer"
@@ -44,7 +50,9 @@ pub fn draw_log_view(
             "Date Selection"
         },
         date_selection.profile_name,
-        date_selection.function_name
+        date_selection.source.label(),
+        stream,
+        date_selection.timezone.label()
     ))
     .style(Style::default().fg(Color::Cyan))
     .block(Block::default().borders(Borders::ALL))
@@ -52,13 +60,12 @@ pub fn draw_log_view(
 
     f.render_widget(title, layout_chunks[0]);
 
-    draw_logs_panel(f, log_viewer, is_loading, layout_chunks[1], focused_panel);
+    draw_logs_panel(f, log_viewer, layout_chunks[1], focused_panel);
 }
 
 fn draw_logs_panel(
     f: &mut Frame,
     log_viewer: Option<&LogViewer>,
-    is_loading: bool,
     area: ratatui::layout::Rect,
     focused_panel: FocusedPanel,
 ) {
@@ -83,52 +90,99 @@ fn draw_logs_panel(
 
     let inner_area = right_panel.inner(area);
 
-    if is_loading {
-        let loading_text = Paragraph::new("Loading logs...")
-            .style(Style::default().fg(Color::Yellow))
-            .alignment(Alignment::Center);
-        f.render_widget(loading_text, inner_area);
-        return;
-    }
-
     if let Some(log_viewer) = log_viewer {
+        let stats_height = if log_viewer.stats.is_some() { 3 } else { 0 };
+        let controls = if log_viewer.server_filter_input.is_some() {
+            "Type a CloudWatch filter pattern | Enter: Apply and reload | Esc: Cancel"
+        } else if log_viewer.timeline_cursor.is_some() {
+            "←→: Pick a bar | Home/End: First/last bar | Enter: Jump to it | z: Zoom into it | Esc: Back to the list | q: Quit"
+        } else if log_viewer.loading.is_some() {
+            "↑↓: Navigate | Enter: Expand | Filter: Type to filter | Esc: Cancel loading | q: Quit"
+        } else if log_viewer.expanded {
+            "Enter: Collapse | Esc: Back | q: Quit"
+        } else if log_viewer.invocations.is_some() {
+            "↑↓: Navigate | →←/Enter: Expand/Collapse | Type: Filter | Ctrl+F: Problems | Ctrl+R: All lines of request | Ctrl+G: Event list | Esc: Back | q: Quit"
+        } else if log_viewer.request_filter.is_some() {
+            "↑↓: Navigate | Enter: Expand | Type: Filter | Ctrl+G: Invocations | Esc: All events | q: Quit"
+        } else if log_viewer.stream_name().is_some() {
+            "↑↓: Navigate | Enter: Expand | Type: Filter | Ctrl+F: Problems | Ctrl+G: Invocations | Ctrl+R: Request | Ctrl+L: Newer events | Ctrl+E: Export | Ctrl+U: Timezone | Esc: Back | q: Quit"
+        } else {
+            "Enter: Expand | Type: Filter | Ctrl+S: Server filter | Ctrl+F: Problems | Ctrl+T: Follow | Ctrl+G: Invocations | Ctrl+R: Request | Ctrl+P: Stats | Ctrl+B: Timeline | Ctrl+←→: Pan | Ctrl+↑↓: Zoom | Ctrl+N: Around event | Ctrl+E: Export | Ctrl+U: Timezone | Esc: Back | q: Quit"
+        };
+        // Hints wrap onto a second line in narrow terminals
+        let controls_height = if controls.chars().count() as u16 + 2 > inner_area.width {
+            4
+        } else {
+            3
+        };
         let log_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Filter
-                Constraint::Min(1),    // Logs
-                Constraint::Length(3), // Helper text
+                Constraint::Length(3),               // Filter
+                Constraint::Length(stats_height),    // Stats
+                Constraint::Length(4),               // Timeline
+                Constraint::Min(1),                  // Logs
+                Constraint::Length(controls_height), // Helper text
             ])
             .margin(1)
             .split(inner_area);
 
-        // Filter input
+        // Filter inputs: local keywords and the CloudWatch filter pattern
+        let filter_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(log_layout[0]);
+
         let filter_input = Paragraph::new(log_viewer.filter_input.as_str())
             .block(Block::default().title("Filter").borders(Borders::ALL));
-        f.render_widget(filter_input, log_layout[0]);
+        f.render_widget(filter_input, filter_layout[0]);
+
+        let (server_filter, server_filter_style) = match &log_viewer.server_filter_input {
+            Some(input) => (format!("{}_", input), Style::default().fg(Color::Yellow)),
+            None => (log_viewer.server_filter.clone(), Style::default()),
+        };
+        let server_filter_input = Paragraph::new(server_filter).block(
+            Block::default()
+                .title("Server filter (Ctrl+S)")
+                .borders(Borders::ALL)
+                .border_style(server_filter_style),
+        );
+        f.render_widget(server_filter_input, filter_layout[1]);
+
+        if let Some(stats) = &log_viewer.stats {
+            draw_stats_panel(f, stats, log_viewer.has_more(), log_layout[1]);
+        }
+
+        draw_timeline(f, log_viewer, log_layout[2]);
 
         // Clear the area before rendering new content
         let clear_widget = ratatui::widgets::Clear;
-        f.render_widget(clear_widget, log_layout[1]);
+        f.render_widget(clear_widget, log_layout[3]);
 
         // Logs content
         if log_viewer.expanded {
-            draw_expanded_log(f, log_viewer, log_layout[1]);
+            draw_expanded_log(f, log_viewer, log_layout[3]);
+        } else if let Some(invocations) = &log_viewer.invocations {
+            draw_invocation_list(f, log_viewer, invocations, log_layout[3]);
         } else {
-            draw_log_list(f, log_viewer, log_layout[1]);
+            draw_log_list(f, log_viewer, log_layout[3]);
         }
 
-        // Controls
-        let controls = if log_viewer.expanded {
-            "Enter: Collapse | Esc: Back | q: Quit"
-        } else {
-            "↑↓: Navigate | Enter: Expand | Filter: Type to filter | Esc: Back | q: Quit"
-        };
-
+        let mut controls_block = Block::default().borders(Borders::ALL);
+        if let Some(status) = &log_viewer.status_message {
+            controls_block = controls_block
+                .title(status.as_str())
+                .title_style(Style::default().fg(Color::Cyan));
+        }
         let controls_widget = Paragraph::new(controls)
             .style(Style::default().fg(Color::Green))
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(controls_widget, log_layout[2]);
+            .wrap(Wrap { trim: true })
+            .block(controls_block);
+        f.render_widget(controls_widget, log_layout[4]);
+
+        if let Some(selected) = log_viewer.export_menu {
+            draw_export_menu(f, selected, log_viewer.filtered_logs.len(), log_layout[3]);
+        }
     } else {
         let placeholder = Paragraph::new("Select date range and press Enter to load logs")
             .style(Style::default().fg(Color::DarkGray))
@@ -137,15 +191,115 @@ fn draw_logs_panel(
     }
 }
 
+/// One line of invocation stats, e.g. `Invocations 120 | Duration p50 ...`.
+fn draw_stats_panel(f: &mut Frame, stats: &InvocationStats, partial: bool, area: Rect) {
+    let label = Style::default().fg(Color::Gray);
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |ms| format!("{:.0} ms", ms));
+
+    let mut spans = vec![
+        Span::styled("Invocations ", label),
+        Span::raw(stats.invocations.to_string()),
+        Span::styled("  │  Duration p50 ", label),
+        Span::raw(ms(stats.p50_ms)),
+        Span::styled("  p90 ", label),
+        Span::raw(ms(stats.p90_ms)),
+        Span::styled("  p99 ", label),
+        Span::raw(ms(stats.p99_ms)),
+        Span::styled("  max ", label),
+        Span::raw(ms(stats.max_ms)),
+        Span::styled("  │  Max memory ", label),
+    ];
+
+    match (stats.max_memory_used_mb, stats.memory_size_mb) {
+        (Some(used), Some(size)) => {
+            let ratio = used / size;
+            let color = if ratio >= 0.9 {
+                Color::Red
+            } else if ratio >= 0.75 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            spans.push(Span::styled(
+                format!("{:.0} / {:.0} MB ({:.0}%)", used, size, ratio * 100.0),
+                Style::default().fg(color),
+            ));
+        }
+        _ => spans.push(Span::raw("-")),
+    }
+
+    let count_style = |count: usize| {
+        if count > 0 {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    };
+    spans.extend([
+        Span::styled("  │  Cold starts ", label),
+        Span::styled(
+            stats.cold_starts.to_string(),
+            count_style(stats.cold_starts),
+        ),
+        Span::styled("  │  Timeouts ", label),
+        Span::styled(
+            stats.timeouts.to_string(),
+            if stats.timeouts > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            },
+        ),
+    ]);
+
+    let title = if partial {
+        "Invocation stats (loaded events so far, Ctrl+P: Hide)"
+    } else {
+        "Invocation stats (Ctrl+P: Hide)"
+    };
+    let panel = Paragraph::new(Line::from(spans))
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(panel, area);
+}
+
+fn draw_export_menu(f: &mut Frame, selected: usize, event_count: usize, area: Rect) {
+    let formats = ExportFormat::all();
+    let popup_area = centered_rect(40, formats.len() as u16 + 4, area);
+
+    let items: Vec<ListItem> = formats
+        .iter()
+        .enumerate()
+        .map(|(i, format)| {
+            let style = if i == selected {
+                Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!(
+                "{} (.{})",
+                format.display_name(),
+                format.extension()
+            ))
+            .style(style)
+        })
+        .collect();
+
+    let menu = List::new(items).block(
+        Block::default()
+            .title(format!("Export {} events", event_count))
+            .title_bottom("Enter: Export | Esc: Cancel")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(menu, popup_area);
+}
+
 fn draw_expanded_log(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::Rect) {
     f.render_widget(Clear, area);
     if let Some(log) = log_viewer.get_selected_log() {
-        let message = log.message.as_deref().unwrap_or("");
-        let timestamp = DateTime::<Local>::from(
-            std::time::UNIX_EPOCH
-                + std::time::Duration::from_millis(log.timestamp.unwrap_or(0) as u64),
-        );
-
+        let message = log.message.as_str();
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -154,11 +308,18 @@ fn draw_expanded_log(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layou
             ])
             .split(area);
 
-        // Header with timestamp
+        // Header with timestamp and stream
         let header = Paragraph::new(vec![Line::from(vec![
             Span::styled("Timestamp: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::styled(
-                timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+                log_viewer
+                    .timezone
+                    .format_millis(log.timestamp, "%Y-%m-%d %H:%M:%S%.3f %Z"),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled("  Stream: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                log.log_stream_name.clone().unwrap_or_default(),
                 Style::default().fg(Color::Cyan),
             ),
         ])])
@@ -236,8 +397,10 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
         );
     }
 
-    let available_width = area.width.saturating_sub(4) as usize; // Subtract 4 for borders and scrollbar
-    let timestamp_width = "YYYY-MM-DD HH:MM:SS ".len();
+    // Subtract 4 for borders and scrollbar
+    let available_width = area.width.saturating_sub(4) as usize;
+    // The stream is shortened to its id, e.g. the Lambda execution environment
+    let timestamp_width = "YYYY-MM-DD HH:MM:SS 12345678 ".len();
     let message_width = available_width.saturating_sub(timestamp_width);
 
     // Calculate visible range
@@ -255,12 +418,7 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
 
     let logs: Vec<ListItem> = visible_logs
         .map(|(i, log)| {
-            let message = log.message.as_deref().unwrap_or("");
-            let timestamp = DateTime::<Local>::from(
-                std::time::UNIX_EPOCH
-                    + std::time::Duration::from_millis(log.timestamp.unwrap_or(0) as u64),
-            );
-
+            let message = log.message.as_str();
             let timestamp_prefix = if Some(i) == log_viewer.selected_log {
                 "→ "
             } else {
@@ -271,18 +429,32 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
                 format!(
                     "{}{} ",
                     timestamp_prefix,
-                    timestamp.format("%Y-%m-%d %H:%M:%S")
+                    log_viewer
+                        .timezone
+                        .format_millis(log.timestamp, "%Y-%m-%d %H:%M:%S")
                 ),
                 Style::default().fg(Color::Gray),
             );
+            let stream_span = Span::styled(
+                format!(
+                    "{:<8} ",
+                    short_stream_name(log.log_stream_name.as_deref().unwrap_or_default())
+                ),
+                Style::default().fg(Color::DarkGray),
+            );
+
+            let tags = problem_tags(log_viewer.problems(log));
+            let tags_width: usize = tags.iter().map(|tag| tag.content.chars().count()).sum();
 
             let mut lines = Vec::new();
             let message_lines: Vec<&str> = message.lines().collect();
 
             // Process first line with timestamp
             if let Some(first_msg) = message_lines.first() {
-                let mut first_line_spans = vec![timestamp_span];
-                let truncated_msg = truncate_to_width(first_msg, message_width);
+                let mut first_line_spans = vec![timestamp_span, stream_span];
+                first_line_spans.extend(tags);
+                let truncated_msg =
+                    truncate_to_width(first_msg, message_width.saturating_sub(tags_width));
 
                 if log_viewer.filter_input.is_empty() {
                     first_line_spans.push(Span::raw(truncated_msg));
@@ -339,14 +511,28 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
         100
     };
 
+    let mut title = match &log_viewer.request_filter {
+        Some(request_id) => format!(
+            "Request {} ({}/{}) {}%",
+            request_id,
+            log_viewer.selected_log.map_or(0, |i| i + 1),
+            total_logs,
+            scroll_percentage
+        ),
+        None => format!(
+            "Logs ({}/{}) {}%",
+            log_viewer.selected_log.map_or(0, |i| i + 1),
+            total_logs,
+            scroll_percentage
+        ),
+    };
+    if log_viewer.problem_filter != ProblemFilter::All {
+        title.push_str(&format!(" [{}]", log_viewer.problem_filter.display_name()));
+    }
     let logs_list = List::new(logs).block(
         Block::default()
-            .title(format!(
-                "Logs ({}/{}) {}%",
-                log_viewer.selected_log.map_or(0, |i| i + 1),
-                total_logs,
-                scroll_percentage
-            ))
+            .title(title)
+            .title(status_indicator(log_viewer).right_aligned())
             .borders(Borders::ALL),
     );
 
@@ -367,23 +553,242 @@ fn draw_log_list(f: &mut Frame, log_viewer: &LogViewer, area: ratatui::layout::R
             start_idx
         };
 
-        let mut scrollbar_state = ScrollbarState::default()
-            .content_length(total_logs)
-            .position(scrollbar_position);
-
-        f.render_stateful_widget(
-            Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓")),
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
+        draw_scrollbar(f, area, total_logs, scrollbar_position);
+    }
+}
+
+fn draw_invocation_list(
+    f: &mut Frame,
+    log_viewer: &LogViewer,
+    invocations: &InvocationList,
+    area: Rect,
+) {
+    let available_width = area.width.saturating_sub(4) as usize;
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let total_rows = invocations.rows.len();
+    let start = invocations
+        .selected
+        .saturating_sub(visible_height / 2)
+        .min(total_rows.saturating_sub(visible_height));
+
+    let items: Vec<ListItem> = invocations
+        .rows
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible_height)
+        .map(|(row_index, row)| {
+            let selected = row_index == invocations.selected;
+            let prefix = if selected { "→ " } else { "  " };
+            let line = match *row {
+                InvocationRow::Invocation(i) => invocation_line(
+                    prefix,
+                    &invocations.invocations[i],
+                    invocations,
+                    log_viewer.timezone,
+                ),
+                InvocationRow::Event(i, event) => {
+                    let log = &invocations.invocations[i].events[event];
+                    let message = log.message.lines().next().unwrap_or_default();
+                    Line::from(vec![
+                        Span::raw(format!("{}    ", prefix)),
+                        Span::styled(
+                            format!(
+                                "{} ",
+                                log_viewer
+                                    .timezone
+                                    .format_millis(log.timestamp, "%H:%M:%S%.3f")
+                            ),
+                            Style::default().fg(Color::Gray),
+                        ),
+                        Span::raw(truncate_to_width(
+                            message,
+                            available_width.saturating_sub(19),
+                        )),
+                    ])
+                }
+            };
+
+            let style = if selected {
+                Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(line).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(format!(
+                "Invocations ({}/{}){}",
+                invocations.selected_position(),
+                invocations.invocations.len(),
+                match log_viewer.problem_filter {
+                    ProblemFilter::All => String::new(),
+                    filter => format!(" [{}]", filter.display_name()),
+                }
+            ))
+            .title(status_indicator(log_viewer).right_aligned())
+            .borders(Borders::ALL),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(list, area);
+
+    if total_rows > visible_height {
+        draw_scrollbar(f, area, total_rows, invocations.selected);
     }
 }
 
+/// e.g. `▸ 2024-01-01 10:00:00 a1b2c3d4 <request id>  103.43 ms  Completed  5 lines  error`
+fn invocation_line(
+    prefix: &str,
+    invocation: &Invocation,
+    invocations: &InvocationList,
+    timezone: DisplayTimezone,
+) -> Line<'static> {
+    let marker = match &invocation.request_id {
+        Some(_) if invocations.is_expanded(invocation) => "▾ ",
+        Some(_) => "▸ ",
+        None => "  ",
+    };
+    let stream = short_stream_name(invocation.log_stream_name.as_deref().unwrap_or_default());
+    let mut spans = vec![
+        Span::raw(format!("{}{}", prefix, marker)),
+        Span::styled(
+            format!(
+                "{} {:<8} ",
+                timezone.format_millis(invocation.start_time, "%Y-%m-%d %H:%M:%S"),
+                stream
+            ),
+            Style::default().fg(Color::Gray),
+        ),
+    ];
+
+    let Some(request_id) = &invocation.request_id else {
+        // A line outside of any request, shown as is
+        let message = invocation
+            .events
+            .first()
+            .and_then(|log| log.message.lines().next())
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            message.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ));
+        return Line::from(spans);
+    };
+
+    let status_color = match invocation.status {
+        InvocationStatus::Completed => Color::Green,
+        InvocationStatus::Incomplete => Color::Yellow,
+        InvocationStatus::Failed | InvocationStatus::TimedOut => Color::Red,
+    };
+    spans.extend([
+        Span::raw(format!("{:<38}", request_id)),
+        Span::raw(format!(
+            "{:>11}  ",
+            invocation
+                .report
+                .as_ref()
+                .map_or("-".to_string(), |report| format!(
+                    "{:.2} ms",
+                    report.duration_ms
+                ))
+        )),
+        Span::styled(
+            format!("{:<11}", invocation.status.display_name()),
+            Style::default().fg(status_color),
+        ),
+        Span::raw(format!("{:>4} lines  ", invocation.events.len())),
+    ]);
+    spans.extend(problem_tags(invocation.problems));
+    if invocation.has_error {
+        spans.push(Span::styled(
+            "✗ error",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// e.g. `[TIMEOUT] [COLD] `, failures in red and cold starts in cyan.
+fn problem_tags(problems: Problems) -> Vec<Span<'static>> {
+    problems
+        .tags()
+        .into_iter()
+        .map(|tag| {
+            let color = if tag == "COLD" {
+                Color::Cyan
+            } else {
+                Color::LightRed
+            };
+            Span::styled(
+                format!("[{}] ", tag),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )
+        })
+        .collect()
+}
+
+fn draw_scrollbar(f: &mut Frame, area: Rect, content_length: usize, position: usize) {
+    let mut scrollbar_state = ScrollbarState::default()
+        .content_length(content_length)
+        .position(position);
+
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓")),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut scrollbar_state,
+    );
+}
+
+fn status_indicator(log_viewer: &LogViewer) -> Line<'static> {
+    // Following is shown alongside loading, it polls independently
+    let follow = if log_viewer.is_follow_paused() {
+        Some(Span::styled(
+            " ⏸ Following paused ",
+            Style::default().fg(Color::Yellow),
+        ))
+    } else if log_viewer.following {
+        Some(Span::styled(
+            " ● Following ",
+            Style::default().fg(Color::Green),
+        ))
+    } else {
+        None
+    };
+
+    let progress = if let Some(loading) = &log_viewer.loading {
+        Some(format!(" {} ", loading.summary()))
+    } else if log_viewer.has_more() {
+        Some(format!(
+            " {} events loaded, more available (Ctrl+L) ",
+            log_viewer.logs.lock().unwrap().len()
+        ))
+    } else if log_viewer.has_older() {
+        Some(format!(
+            " {} events loaded, older ones load when scrolling up ",
+            log_viewer.logs.lock().unwrap().len()
+        ))
+    } else {
+        None
+    };
+
+    Line::from(
+        follow
+            .into_iter()
+            .chain(progress.map(|text| Span::styled(text, Style::default().fg(Color::Yellow))))
+            .collect::<Vec<_>>(),
+    )
+}
+
 fn add_highlighted_message_spans(spans: &mut Vec<Span<'static>>, text: &str, filter: &str) {
     let keywords: Vec<&str> = filter.split_whitespace().collect();
     let mut last_pos = 0;
@@ -438,7 +843,20 @@ fn format_log_message(message: &str) -> Vec<Line<'static>> {
         // Handle non-JSON log messages
         for line in message.lines() {
             let line_string = line.to_string(); // Convert to owned String
-            if line.contains("ERROR") || line.contains("error") {
+            let problems = line_problems(line);
+            if problems.is_failure() {
+                lines.push(Line::from(Span::styled(
+                    line_string,
+                    Style::default()
+                        .fg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                )));
+            } else if problems.cold_start || line.starts_with("INIT_START") {
+                lines.push(Line::from(Span::styled(
+                    line_string,
+                    Style::default().fg(Color::Cyan),
+                )));
+            } else if line.contains("ERROR") || line.contains("error") {
                 lines.push(Line::from(Span::styled(
                     line_string,
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
@@ -467,53 +885,14 @@ fn format_log_message(message: &str) -> Vec<Line<'static>> {
     lines
 }
 
-// Add this function to format JSON content
-
-// Add this helper function at the end of the file
-fn wrap_text(text: &str, width: usize) -> Vec<String> {
-    let mut wrapped = Vec::new();
-    let mut line = String::new();
-    let mut line_length = 0;
-
-    for word in text.split_whitespace() {
-        let word_length = word.len();
-
-        if line_length + word_length + 1 <= width {
-            // Add word to current line
-            if !line.is_empty() {
-                line.push(' ');
-                line_length += 1;
-            }
-            line.push_str(word);
-            line_length += word_length;
-        } else {
-            // Start new line
-            if !line.is_empty() {
-                wrapped.push(line);
-            }
-            line = word.to_string();
-            line_length = word_length;
-        }
-    }
-
-    if !line.is_empty() {
-        wrapped.push(line);
-    }
-
-    // Handle empty input or single long words
-    if wrapped.is_empty() {
-        wrapped.push(text.to_string());
-    }
-
-    wrapped
-}
-
-// Add this helper function to truncate text
 fn truncate_to_width(text: &str, width: usize) -> String {
-    if text.len() <= width {
+    if text.chars().count() <= width {
         text.to_string()
     } else {
-        let mut truncated = text.chars().take(width - 3).collect::<String>();
+        let mut truncated = text
+            .chars()
+            .take(width.saturating_sub(3))
+            .collect::<String>();
         truncated.push_str("...");
         truncated
     }
@@ -1,4 +1,9 @@
 pub mod date_selection;
+pub mod error_popup;
 pub mod function_list_view;
+pub mod insights_view;
+pub mod log_group_list_view;
 pub mod log_view;
 pub mod profile_list_view;
+pub mod stream_list_view;
+pub mod timeline_view;
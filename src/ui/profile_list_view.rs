@@ -1,13 +1,18 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-use crate::app_state::profile_selection::ProfileSelection;
+use crate::app_state::{loading::LoadingState, profile_selection::ProfileSelection, FocusedPanel};
 
-pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
+pub fn draw_profile_selection(
+    f: &mut Frame,
+    state: &mut ProfileSelection,
+    loading: Option<&LoadingState>,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -16,7 +21,7 @@ pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
             Constraint::Min(0),    // Main content
             Constraint::Length(3), // Controls
         ])
-        .split(f.size());
+        .split(f.area());
 
     // Title
     let title = Paragraph::new("AWS Profile Selection")
@@ -24,7 +29,19 @@ pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
-    // Profile List
+    // Profiles, with the pinned functions of the highlighted one beside them
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+    let border = |panel: FocusedPanel| {
+        Style::default().fg(if state.focused_panel == panel {
+            Color::Yellow
+        } else {
+            Color::White
+        })
+    };
+
     let profiles: Vec<ListItem> = state
         .profiles
         .iter()
@@ -32,12 +49,63 @@ pub fn draw_profile_selection(f: &mut Frame, state: &mut ProfileSelection) {
         .collect();
 
     let profiles_list = List::new(profiles)
-        .block(Block::default().title("AWS Profiles").borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title("AWS Profiles")
+                .borders(Borders::ALL)
+                .border_style(border(FocusedPanel::Left)),
+        )
         .highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
-    f.render_stateful_widget(profiles_list, chunks[1], &mut state.list_state);
+    f.render_stateful_widget(profiles_list, main_chunks[0], &mut state.list_state);
+
+    let pinned_block = Block::default()
+        .title("Favorites & recent")
+        .borders(Borders::ALL)
+        .border_style(border(FocusedPanel::Right));
+    let pinned = state.pinned_functions();
+    if pinned.is_empty() {
+        let hint = Paragraph::new("Functions you open or star (Ctrl+F) show up here")
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(Wrap { trim: true })
+            .block(pinned_block);
+        f.render_widget(hint, main_chunks[1]);
+    } else {
+        let items: Vec<ListItem> = pinned
+            .iter()
+            .map(|name| {
+                ListItem::new(if state.history.is_favorite(name) {
+                    Line::from(vec![
+                        Span::styled("★ ", Style::default().fg(Color::Yellow)),
+                        Span::raw(name.to_string()),
+                    ])
+                } else {
+                    Line::from(format!("  {}", name))
+                })
+            })
+            .collect();
+        // No highlight until the panel is focused
+        let mut unfocused = ListState::default();
+        let pinned_state = if state.focused_panel == FocusedPanel::Right {
+            &mut state.pinned_state
+        } else {
+            &mut unfocused
+        };
+        let pinned_list = List::new(items)
+            .block(pinned_block)
+            .highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+        f.render_stateful_widget(pinned_list, main_chunks[1], pinned_state);
+    }
 
     // Controls
-    let controls = Paragraph::new("↑↓ or j/k: Navigate profiles | Enter: Select | q: Quit")
+    let controls_text = match loading {
+        Some(loading) => format!("{} | Esc: Cancel | q: Quit", loading.summary()),
+        None if state.focused_panel == FocusedPanel::Right => {
+            "↑↓ or j/k: Navigate functions | Enter: Open logs | Tab: Profiles | q: Quit".to_string()
+        }
+        None => "↑↓ or j/k: Navigate profiles | Enter: Select | Tab: Favorites & recent | q: Quit"
+            .to_string(),
+    };
+    let controls = Paragraph::new(controls_text)
         .style(Style::default().fg(Color::Green))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(controls, chunks[2]);
@@ -0,0 +1,108 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::app_state::stream_selection::StreamSelection;
+use crate::utils::time_utils::DisplayTimezone;
+
+pub fn draw_stream_selection(
+    f: &mut Frame,
+    profile_name: &str,
+    state: &StreamSelection,
+    timezone: DisplayTimezone,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Filter
+            Constraint::Min(0),    // Streams
+            Constraint::Length(3), // Controls
+        ])
+        .split(f.area());
+
+    // Title
+    let title = Paragraph::new(format!(
+        "Log Streams | Profile: {} | {} | Time: {}",
+        profile_name,
+        state.source.label(),
+        timezone.label()
+    ))
+    .style(Style::default().fg(Color::Cyan))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(title, chunks[0]);
+
+    let filter_input = Paragraph::new(state.filter_input.as_str())
+        .block(Block::default().title("Filter").borders(Borders::ALL));
+    f.render_widget(filter_input, chunks[1]);
+
+    // Streams
+    let status = match &state.loading {
+        Some(loading) => Span::styled(
+            format!(" {} ", loading.summary()),
+            Style::default().fg(Color::Yellow),
+        ),
+        None => Span::raw(""),
+    };
+
+    let header = Row::new(["Stream", "First event", "Last event"]).style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = state
+        .filtered_streams
+        .iter()
+        .map(|stream| {
+            Row::new([
+                Cell::from(stream.name.clone()),
+                Cell::from(format_event_time(stream.first_event_time, timezone)),
+                Cell::from(format_event_time(stream.last_event_time, timezone)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(19),
+            Constraint::Length(19),
+        ],
+    )
+    .header(header)
+    .column_spacing(2)
+    .block(
+        Block::default()
+            .title(format!(
+                "Streams by last event ({}/{})",
+                (state.selected_index + 1).min(state.filtered_streams.len()),
+                state.filtered_streams.len()
+            ))
+            .title(Line::from(status).right_aligned())
+            .borders(Borders::ALL),
+    )
+    .row_highlight_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray));
+
+    let mut table_state = TableState::default().with_selected(Some(state.selected_index));
+    f.render_stateful_widget(table, chunks[2], &mut table_state);
+
+    // Controls
+    let controls = Paragraph::new(
+        "↑↓: Navigate | Enter: Open stream | Type: Filter | Ctrl+U: Timezone | Esc: Back | q: Quit",
+    )
+    .style(Style::default().fg(Color::Green))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(controls, chunks[3]);
+}
+
+fn format_event_time(timestamp: Option<i64>, timezone: DisplayTimezone) -> String {
+    timestamp.map_or("-".to_string(), |ms| {
+        timezone.format_millis(ms, "%Y-%m-%d %H:%M:%S")
+    })
+}
@@ -0,0 +1,128 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders},
+    Frame,
+};
+
+use crate::utils::time_utils::DisplayTimezone;
+use crate::{app_state::log_viewer::LogViewer, timeline::Histogram};
+
+const BAR_SYMBOLS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+/// Bars of the event rate across the viewer's range, errors stacked at the
+/// bottom in red. A marker on the bottom border points at the selected event.
+pub fn draw_timeline(f: &mut Frame, log_viewer: &LogViewer, area: Rect) {
+    let histogram = log_viewer.histogram();
+    let focused = log_viewer.timeline_cursor.is_some();
+
+    let title = match log_viewer.timeline_cursor {
+        Some(cursor) => {
+            let (start, end) = histogram.bucket_range(cursor);
+            let bucket = histogram.buckets[cursor];
+            format!(
+                "{} – {}: {} events, {} errors",
+                format_time(start, log_viewer.timezone),
+                format_time(end, log_viewer.timezone),
+                bucket.events,
+                bucket.errors
+            )
+        }
+        None => format!(
+            "Timeline {} – {} ({} per bar)",
+            format_time(histogram.start, log_viewer.timezone),
+            format_time(histogram.end, log_viewer.timezone),
+            format_span(histogram.bucket_millis())
+        ),
+    };
+    let block = Block::default()
+        .title(title)
+        .title(
+            Line::from(format!("max {}/bar", histogram.max_events()))
+                .right_aligned()
+                .style(Style::default().fg(Color::DarkGray)),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if focused { Color::Yellow } else { Color::White }));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+
+    let selected_bucket = log_viewer
+        .get_selected_log()
+        .and_then(|log| histogram.bucket_of(log.timestamp));
+    let max_events = histogram.max_events().max(1);
+    let levels = inner.height as usize * 8;
+    let buffer = f.buffer_mut();
+
+    for x in 0..inner.width {
+        let buckets = column_buckets(&histogram, x, inner.width);
+        let (events, errors) = buckets
+            .clone()
+            .map(|i| histogram.buckets[i])
+            .fold((0, 0), |(events, errors), bucket| {
+                (events + bucket.events, errors + bucket.errors)
+            });
+        // Any event shows at least the lowest bar
+        let height = |count: usize| (count * levels).div_ceil(max_events);
+        let (bar, error_bar) = (height(events), height(errors));
+
+        let cursor = log_viewer
+            .timeline_cursor
+            .is_some_and(|cursor| buckets.contains(&cursor));
+        let background = if cursor {
+            Color::DarkGray
+        } else {
+            Color::Reset
+        };
+
+        for row in 0..inner.height {
+            let low = row as usize * 8;
+            let fill = bar.saturating_sub(low).min(8);
+            // The cell takes the color of what fills most of it
+            let color = if error_bar.saturating_sub(low) * 2 >= fill && fill > 0 {
+                Color::Red
+            } else {
+                Color::Blue
+            };
+            if let Some(cell) = buffer.cell_mut((inner.x + x, inner.bottom() - 1 - row)) {
+                cell.set_symbol(BAR_SYMBOLS[fill])
+                    .set_style(Style::default().fg(color).bg(background));
+            }
+        }
+
+        if selected_bucket.is_some_and(|selected| buckets.contains(&selected)) {
+            if let Some(cell) = buffer.cell_mut((inner.x + x, area.bottom() - 1)) {
+                cell.set_symbol("▲")
+                    .set_style(Style::default().fg(Color::Yellow));
+            }
+        }
+    }
+}
+
+/// The buckets shown in column `x` of `width`: a bucket spans several
+/// columns in wide areas, a column holds several buckets in narrow ones.
+fn column_buckets(histogram: &Histogram, x: u16, width: u16) -> std::ops::Range<usize> {
+    let count = histogram.buckets.len();
+    let first = x as usize * count / width as usize;
+    let last = ((x as usize + 1) * count / width as usize).max(first + 1);
+    first..last.min(count)
+}
+
+fn format_time(timestamp: i64, timezone: DisplayTimezone) -> String {
+    timezone.format_millis(timestamp, "%m-%d %H:%M:%S")
+}
+
+/// e.g. `30s`, `5m`, `2h 30m`
+fn format_span(millis: i64) -> String {
+    let seconds = millis / 1000;
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        _ if seconds % 3600 / 60 == 0 => format!("{}h", seconds / 3600),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
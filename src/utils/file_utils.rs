@@ -1,7 +1,10 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::backend::{FunctionInfo, LogGroupInfo};
+
 pub fn get_cache_dir() -> Result<PathBuf> {
     let cache_dir = dirs::cache_dir()
         .ok_or_else(|| anyhow::anyhow!("Could not find cache directory"))?
@@ -19,14 +22,36 @@ pub fn get_functions_cache_path(profile_name: &str, region: &str) -> Result<Path
     Ok(cache_dir.join(format!("functions_{}_{}.cache", profile_name, region)))
 }
 
-pub fn cache_functions(profile_name: &str, region: &str, functions: &[String]) -> Result<()> {
+/// Bumped when the layout of the function cache changes; caches written by
+/// other versions are ignored and fetched again.
+const FUNCTIONS_CACHE_VERSION: u32 = 2;
+
+/// The cached function list of one profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionsCache {
+    pub version: u32,
+    /// When the functions were listed, in epoch millis.
+    pub fetched_at: i64,
+    pub functions: Vec<FunctionInfo>,
+}
+
+pub fn cache_functions(
+    profile_name: &str,
+    region: &str,
+    fetched_at: i64,
+    functions: &[FunctionInfo],
+) -> Result<()> {
     let cache_path = get_functions_cache_path(profile_name, region)?;
-    let cache_content = serde_json::to_string(functions)?;
-    fs::write(cache_path, cache_content)?;
+    let cache = FunctionsCache {
+        version: FUNCTIONS_CACHE_VERSION,
+        fetched_at,
+        functions: functions.to_vec(),
+    };
+    fs::write(cache_path, serde_json::to_string(&cache)?)?;
     Ok(())
 }
 
-pub fn load_cached_functions(profile_name: &str, region: &str) -> Result<Option<Vec<String>>> {
+pub fn load_cached_functions(profile_name: &str, region: &str) -> Result<Option<FunctionsCache>> {
     let cache_path = get_functions_cache_path(profile_name, region)?;
 
     if !cache_path.exists() {
@@ -34,6 +59,187 @@ pub fn load_cached_functions(profile_name: &str, region: &str) -> Result<Option<
     }
 
     let cache_content = fs::read_to_string(cache_path)?;
-    let functions: Vec<String> = serde_json::from_str(&cache_content)?;
-    Ok(Some(functions))
+    Ok(parse_functions_cache(&cache_content))
+}
+
+/// Caches of older versions, e.g. a bare list of names, count as no cache.
+fn parse_functions_cache(content: &str) -> Option<FunctionsCache> {
+    serde_json::from_str::<FunctionsCache>(content)
+        .ok()
+        .filter(|cache| cache.version == FUNCTIONS_CACHE_VERSION)
+}
+
+pub fn get_log_groups_cache_path(profile_name: &str, region: &str) -> Result<PathBuf> {
+    let cache_dir = get_cache_dir()?;
+    Ok(cache_dir.join(format!("log_groups_{}_{}.cache", profile_name, region)))
+}
+
+pub fn cache_log_groups(
+    profile_name: &str,
+    region: &str,
+    log_groups: &[LogGroupInfo],
+) -> Result<()> {
+    let cache_path = get_log_groups_cache_path(profile_name, region)?;
+    let cache_content = serde_json::to_string(log_groups)?;
+    fs::write(cache_path, cache_content)?;
+    Ok(())
+}
+
+pub fn load_cached_log_groups(
+    profile_name: &str,
+    region: &str,
+) -> Result<Option<Vec<LogGroupInfo>>> {
+    let cache_path = get_log_groups_cache_path(profile_name, region)?;
+
+    if !cache_path.exists() {
+        return Ok(None);
+    }
+
+    let cache_content = fs::read_to_string(cache_path)?;
+    let log_groups: Vec<LogGroupInfo> = serde_json::from_str(&cache_content)?;
+    Ok(Some(log_groups))
+}
+
+/// Deletes the cached function and log group lists of every profile.
+/// Favorites and recently used functions are kept.
+pub fn clear_cache() -> Result<()> {
+    for entry in fs::read_dir(get_cache_dir()?)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "cache")
+        {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// How many recently used functions are remembered per profile.
+const MAX_RECENT_FUNCTIONS: usize = 10;
+
+/// The favorite and recently used functions of one profile.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionHistory {
+    pub favorites: Vec<String>,
+    /// Most recent first.
+    pub recent: Vec<String>,
+}
+
+impl FunctionHistory {
+    pub fn is_favorite(&self, function_name: &str) -> bool {
+        self.favorites.iter().any(|name| name == function_name)
+    }
+
+    pub fn toggle_favorite(&mut self, function_name: &str) {
+        if self.is_favorite(function_name) {
+            self.favorites.retain(|name| name != function_name);
+        } else {
+            self.favorites.push(function_name.to_string());
+            self.favorites.sort();
+        }
+    }
+
+    pub fn record_use(&mut self, function_name: &str) {
+        self.recent.retain(|name| name != function_name);
+        self.recent.insert(0, function_name.to_string());
+        self.recent.truncate(MAX_RECENT_FUNCTIONS);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.favorites.is_empty() && self.recent.is_empty()
+    }
+}
+
+pub fn get_function_history_path(profile_name: &str, region: &str) -> Result<PathBuf> {
+    let cache_dir = get_cache_dir()?;
+    Ok(cache_dir.join(format!("history_{}_{}.json", profile_name, region)))
+}
+
+pub fn save_function_history(
+    profile_name: &str,
+    region: &str,
+    history: &FunctionHistory,
+) -> Result<()> {
+    let path = get_function_history_path(profile_name, region)?;
+    fs::write(path, serde_json::to_string(history)?)?;
+    Ok(())
+}
+
+pub fn load_function_history(profile_name: &str, region: &str) -> Result<FunctionHistory> {
+    let path = get_function_history_path(profile_name, region)?;
+
+    if !path.exists() {
+        return Ok(FunctionHistory::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_only_caches_of_the_current_version() {
+        let cache = FunctionsCache {
+            version: FUNCTIONS_CACHE_VERSION,
+            fetched_at: 1_792_065_600_000,
+            functions: vec![FunctionInfo::named("orders")],
+        };
+        let parsed = parse_functions_cache(&serde_json::to_string(&cache).unwrap()).unwrap();
+        assert_eq!(parsed.fetched_at, cache.fetched_at);
+        assert_eq!(parsed.functions, cache.functions);
+
+        let older = r#"{"version":1,"fetched_at":0,"functions":[{"name":"orders"}]}"#;
+        assert!(parse_functions_cache(older).is_none());
+        // The first cache was a bare list of names
+        assert!(parse_functions_cache(r#"["orders","users"]"#).is_none());
+        assert!(parse_functions_cache("").is_none());
+    }
+
+    #[test]
+    fn toggles_favorites() {
+        let mut history = FunctionHistory::default();
+        history.toggle_favorite("users");
+        history.toggle_favorite("orders");
+        assert_eq!(history.favorites, ["orders", "users"]);
+        assert!(history.is_favorite("orders"));
+
+        history.toggle_favorite("orders");
+        assert_eq!(history.favorites, ["users"]);
+        assert!(!history.is_favorite("orders"));
+        history.toggle_favorite("users");
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn keeps_the_most_recently_used_functions() {
+        let mut history = FunctionHistory::default();
+        for i in 0..12 {
+            history.record_use(&format!("function-{}", i));
+        }
+        let expected: Vec<String> = (2..12).rev().map(|i| format!("function-{}", i)).collect();
+        assert_eq!(history.recent, expected);
+
+        // A reused function moves to the front instead of appearing twice
+        history.record_use("function-5");
+        assert_eq!(history.recent.len(), MAX_RECENT_FUNCTIONS);
+        assert_eq!(
+            history.recent[..3],
+            ["function-5", "function-11", "function-10"]
+        );
+        assert_eq!(
+            history
+                .recent
+                .iter()
+                .filter(|name| *name == "function-5")
+                .count(),
+            1
+        );
+        history.record_use("function-5");
+        assert_eq!(history.recent[..2], ["function-5", "function-11"]);
+    }
 }
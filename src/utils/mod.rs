@@ -1,2 +1,3 @@
 pub mod file_utils;
+pub mod time_utils;
 pub mod ui_utils;
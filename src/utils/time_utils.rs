@@ -0,0 +1,488 @@
+use anyhow::{anyhow, Result};
+use chrono::{
+    DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset, SecondsFormat,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::fmt;
+
+// ISO-8601 timestamps with an offset; `Z` is rewritten to `+00:00` first
+const OFFSET_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M%:z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%z",
+];
+// ISO-8601 timestamps without an offset are in the timezone parsed in
+const ZONED_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+
+const TIME_HELP: &str = "use `now`, a duration like `15m` or `2h ago`, `today` or `yesterday` \
+with an optional `HH:MM`, an ISO-8601 timestamp or epoch millis, optionally followed by `+30m` or `-1h`";
+
+/// The timezone timestamps are shown in and typed times are read in.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum DisplayTimezone {
+    #[default]
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl TryFrom<String> for DisplayTimezone {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> Result<Self> {
+        Self::parse(&name)
+    }
+}
+
+impl DisplayTimezone {
+    /// `local`, `utc` or an IANA name such as `Europe/Berlin`.
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "utc" | "z" => Ok(Self::Utc),
+            _ => name.trim().parse::<Tz>().map(Self::Named).map_err(|_| {
+                anyhow!(
+                    "unknown timezone '{}' (use local, utc or a name like Europe/Berlin)",
+                    name
+                )
+            }),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Local => "Local",
+            Self::Utc => "UTC",
+            Self::Named(tz) => tz.name(),
+        }
+    }
+
+    /// Local, then UTC, then the configured named timezone if there is one.
+    pub fn next(self, configured: DisplayTimezone) -> Self {
+        match (self, configured) {
+            (Self::Local, _) => Self::Utc,
+            (Self::Utc, Self::Named(_)) => configured,
+            _ => Self::Local,
+        }
+    }
+
+    pub fn format<T: TimeZone>(&self, time: &DateTime<T>, format: &str) -> String {
+        match self {
+            Self::Local => time.with_timezone(&Local).format(format).to_string(),
+            Self::Utc => time.with_timezone(&Utc).format(format).to_string(),
+            Self::Named(tz) => time.with_timezone(tz).format(format).to_string(),
+        }
+    }
+
+    pub fn format_millis(&self, millis: i64, format: &str) -> String {
+        Utc.timestamp_millis_opt(millis)
+            .single()
+            .map_or("-".to_string(), |time| self.format(&time, format))
+    }
+
+    /// RFC 3339 with milliseconds, e.g. `2026-10-15T14:00:00.000Z` in UTC.
+    pub fn rfc3339_millis(&self, millis: i64) -> String {
+        let Some(time) = Utc.timestamp_millis_opt(millis).single() else {
+            return millis.to_string();
+        };
+        match self {
+            Self::Local => time
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Millis, false),
+            Self::Utc => time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            Self::Named(tz) => time
+                .with_timezone(tz)
+                .to_rfc3339_opts(SecondsFormat::Millis, false),
+        }
+    }
+
+    /// Changes the wall clock time of `time` as seen in this timezone, e.g.
+    /// its month. `None` if the result does not exist.
+    pub fn map_wall_time(
+        &self,
+        time: DateTime<Local>,
+        change: impl FnOnce(NaiveDateTime) -> Option<NaiveDateTime>,
+    ) -> Option<DateTime<Local>> {
+        fn map<T: TimeZone>(
+            time: DateTime<Local>,
+            zone: &T,
+            change: impl FnOnce(NaiveDateTime) -> Option<NaiveDateTime>,
+        ) -> Option<DateTime<Local>> {
+            let changed = change(time.with_timezone(zone).naive_local())?;
+            zone.from_local_datetime(&changed)
+                .earliest()
+                .map(|time| time.with_timezone(&Local))
+        }
+        match self {
+            Self::Local => map(time, &Local, change),
+            Self::Utc => map(time, &Utc, change),
+            Self::Named(tz) => map(time, tz, change),
+        }
+    }
+
+    /// `parse_time_arg` with dates and times of day in this timezone.
+    pub fn parse_time_arg(&self, input: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+        match self {
+            Self::Local => parse_time_arg(input, now),
+            Self::Utc => parse_time_arg(input, now.with_timezone(&Utc))
+                .map(|time| time.with_timezone(&Local)),
+            Self::Named(tz) => {
+                parse_time_arg(input, now.with_timezone(tz)).map(|time| time.with_timezone(&Local))
+            }
+        }
+    }
+
+    /// `parse_time_range` with dates and times of day in this timezone.
+    pub fn parse_time_range(
+        &self,
+        input: &str,
+        now: DateTime<Local>,
+    ) -> Result<(DateTime<Local>, DateTime<Local>)> {
+        fn to_local<T: TimeZone>(
+            (from, to): (DateTime<T>, DateTime<T>),
+        ) -> (DateTime<Local>, DateTime<Local>) {
+            (from.with_timezone(&Local), to.with_timezone(&Local))
+        }
+        match self {
+            Self::Local => parse_time_range(input, now),
+            Self::Utc => parse_time_range(input, now.with_timezone(&Utc)).map(to_local),
+            Self::Named(tz) => parse_time_range(input, now.with_timezone(tz)).map(to_local),
+        }
+    }
+}
+
+/// Parses a point in time relative to `now`: `now`, a duration ago such as
+/// `30s`, `15m` or `2h ago`, `today`/`yesterday` with an optional time of
+/// day, a time of day alone, an ISO-8601 timestamp or epoch millis. A
+/// trailing `+30m` or `-1h` shifts it. Dates and times without an offset are
+/// in `now`'s timezone.
+pub fn parse_time_arg<T: Zone>(input: &str, now: DateTime<T>) -> Result<DateTime<T>> {
+    let input = input.trim();
+    match split_offset(input) {
+        Some((base, offset)) => shift(parse_point(base, now)?, offset),
+        None => parse_point(input, now),
+    }
+}
+
+/// Parses a range: `<start>..<end>` with both ends as in `parse_time_arg`
+/// (an end that is only a time of day is on the start's day, `+1h` is
+/// relative to the start), a duration up to now (`15m`), a point and a
+/// length (`2026-10-15T14:00Z+30m`), a whole day (`yesterday`,
+/// `2026-10-15`), or a point alone for the range from it up to now.
+pub fn parse_time_range<T: Zone>(
+    input: &str,
+    now: DateTime<T>,
+) -> Result<(DateTime<T>, DateTime<T>)> {
+    let (from, to) = parse_range_ends(input, now)?;
+    if from >= to {
+        return Err(anyhow!(
+            "the range ends ({}) before it starts ({})",
+            to.format("%Y-%m-%d %H:%M:%S"),
+            from.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    Ok((from, to))
+}
+
+/// Checks the syntax of a range without resolving it: whether e.g.
+/// `today 09:00..now` is a valid range depends on when it's used.
+pub fn check_time_range(input: &str) -> Result<()> {
+    // UTC skips no times of day
+    parse_range_ends(input, Utc::now()).map(|_| ())
+}
+
+/// The ends of a `parse_time_range` range, in either order.
+fn parse_range_ends<T: Zone>(input: &str, now: DateTime<T>) -> Result<(DateTime<T>, DateTime<T>)> {
+    let input = input.trim();
+    let lower = input.to_ascii_lowercase();
+
+    let (from, to) = if let Some((start, end)) = input.split_once("..") {
+        let from = parse_time_arg(start, now)?;
+        let end = end.trim();
+        let to = if let Some(length) = end.strip_prefix('+') {
+            shift(from, parse_duration(length)?)?
+        } else if let Some(time) = parse_time_of_day(end) {
+            zoned_datetime(&now.timezone(), from.date_naive().and_time(time))?
+        } else {
+            parse_time_arg(end, now)?
+        };
+        (from, to)
+    } else if let Some((base, length)) = split_offset(input) {
+        // The range runs from the point for `+length`, or up to it for `-length`
+        let point = parse_point(base, now)?;
+        if length >= Duration::zero() {
+            (point, shift(point, length)?)
+        } else {
+            (shift(point, length)?, point)
+        }
+    } else if lower == "today"
+        || lower == "yesterday"
+        || NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok()
+    {
+        let start = parse_point(input, now)?;
+        (start, shift(start, Duration::days(1))?.min(now))
+    } else if let Ok(duration) = parse_duration(input) {
+        (shift(now, -duration)?, now)
+    } else {
+        (parse_point(input, now)?, now)
+    };
+    Ok((from, to))
+}
+
+/// The timezones the parsers work in; their offsets are plain values.
+pub trait Zone: TimeZone<Offset = Self::Value> {
+    type Value: Offset + Copy + fmt::Display;
+}
+
+impl<T> Zone for T
+where
+    T: TimeZone,
+    T::Offset: Copy + fmt::Display,
+{
+    type Value = T::Offset;
+}
+
+/// Splits a trailing `+30m` or `-1h` off `input`.
+fn split_offset(input: &str) -> Option<(&str, Duration)> {
+    let at = input.rfind(['+', '-']).filter(|&at| at > 0)?;
+    // Dates and UTC offsets end in digits, so they are not taken for a duration
+    let duration = parse_duration(&input[at + 1..]).ok()?;
+    let offset = if input[at..].starts_with('-') {
+        -duration
+    } else {
+        duration
+    };
+    Some((input[..at].trim_end(), offset))
+}
+
+fn parse_point<T: Zone>(input: &str, now: DateTime<T>) -> Result<DateTime<T>> {
+    let input = input.trim();
+    let lower = input.to_ascii_lowercase();
+    let zone = now.timezone();
+    if lower == "now" {
+        return Ok(now);
+    }
+
+    for (day, days_ago) in [("today", 0), ("yesterday", 1)] {
+        if let Some(time) = lower.strip_prefix(day) {
+            let date = now.date_naive() - Duration::days(days_ago);
+            let time = match time.trim() {
+                "" => NaiveTime::MIN,
+                time => parse_time_of_day(time)
+                    .ok_or_else(|| anyhow!("invalid time of day '{}' (use HH:MM)", time))?,
+            };
+            return zoned_datetime(&zone, date.and_time(time));
+        }
+    }
+    if let Some(time) = parse_time_of_day(input) {
+        return zoned_datetime(&zone, now.date_naive().and_time(time));
+    }
+    if let Some(duration) = lower.strip_suffix("ago") {
+        return shift(now, -parse_duration(duration.trim())?);
+    }
+    if !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit()) {
+        let millis: i64 = input
+            .parse()
+            .map_err(|_| anyhow!("invalid epoch millis '{}'", input))?;
+        return zone
+            .timestamp_millis_opt(millis)
+            .single()
+            .ok_or_else(|| anyhow!("epoch millis '{}' out of range", input));
+    }
+    if let Some(timestamp) = parse_timestamp(input, &zone)? {
+        return Ok(timestamp);
+    }
+
+    match parse_duration(input) {
+        Ok(duration) => shift(now, -duration),
+        // A number with a unit that is not known gets the duration's error
+        Err(error) if input.starts_with(|c: char| c.is_ascii_digit()) && !input.contains('-') => {
+            Err(error)
+        }
+        Err(_) => Err(anyhow!("unrecognised time '{}': {}", input, TIME_HELP)),
+    }
+}
+
+/// ISO-8601 timestamps, with `T` or a space between date and time, or a
+/// date alone for its start.
+fn parse_timestamp<T: Zone>(input: &str, zone: &T) -> Result<Option<DateTime<T>>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(input) {
+        return Ok(Some(timestamp.with_timezone(zone)));
+    }
+
+    let normalized = input.replacen(' ', "T", 1);
+    let with_offset = match normalized.strip_suffix(['Z', 'z']) {
+        Some(utc) => format!("{}+00:00", utc),
+        None => normalized.clone(),
+    };
+    for format in OFFSET_FORMATS {
+        if let Ok(timestamp) = DateTime::parse_from_str(&with_offset, format) {
+            return Ok(Some(timestamp.with_timezone(zone)));
+        }
+    }
+    for format in ZONED_FORMATS {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(&normalized, format) {
+            return zoned_datetime(zone, timestamp).map(Some);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return zoned_datetime(zone, date.and_time(NaiveTime::MIN)).map(Some);
+    }
+    Ok(None)
+}
+
+fn parse_time_of_day(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+        .ok()
+}
+
+fn zoned_datetime<T: Zone>(zone: &T, timestamp: NaiveDateTime) -> Result<DateTime<T>> {
+    zone.from_local_datetime(&timestamp)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} is skipped by a daylight saving change", timestamp))
+}
+
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("missing unit in duration '{}' (use s, m, h, d or w)", input))?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| anyhow!("invalid duration '{}'", input))?;
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => {
+            return Err(anyhow!(
+                "unknown unit '{}' in duration '{}' (use s, m, h, d or w)",
+                unit,
+                input
+            ))
+        }
+    };
+    duration.ok_or_else(|| anyhow!("duration out of range: '{}'", input))
+}
+
+/// `time + by`, or an error instead of an overflow for far-off times.
+fn shift<T: Zone>(time: DateTime<T>, by: Duration) -> Result<DateTime<T>> {
+    time.checked_add_signed(by)
+        .ok_or_else(|| anyhow!("duration out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::days(14));
+        assert!(parse_duration("15").is_err());
+        assert!(parse_duration("15y").is_err());
+    }
+
+    #[test]
+    fn huge_durations_are_errors() {
+        assert!(parse_duration("99999999999999999d").is_err());
+        assert!(parse_time_arg("999999999w", now()).is_err());
+        assert!(parse_time_arg("999999999w ago", now()).is_err());
+        assert!(parse_time_arg("now+999999999w", now()).is_err());
+        assert!(parse_time_range("999999999w", now()).is_err());
+        assert!(parse_time_range("2h..+999999999w", now()).is_err());
+        assert!(parse_time_range("now-999999999w", now()).is_err());
+    }
+
+    #[test]
+    fn checks_range_syntax_without_resolving() {
+        assert!(check_time_range("today 09:00..now").is_ok());
+        assert!(check_time_range("yesterday 23:00..01:00").is_ok());
+        assert!(check_time_range("2h ago..tomorrow").is_err());
+        assert!(check_time_range("today 25:00..now").is_err());
+    }
+
+    #[test]
+    fn parses_ranges() {
+        let (from, to) = parse_time_range("15m", now()).unwrap();
+        assert_eq!((from, to), (now() - Duration::minutes(15), now()));
+
+        let (from, to) = parse_time_range("yesterday 09:00..10:30", now()).unwrap();
+        assert_eq!(from, Utc.with_ymd_and_hms(2026, 10, 14, 9, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2026, 10, 14, 10, 30, 0).unwrap());
+
+        assert!(parse_time_range("now..1h ago", now()).is_err());
+    }
+
+    fn utc(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_points() {
+        let cases = [
+            ("now", now()),
+            ("2h ago", utc(10, 0)),
+            ("today 09:30", utc(9, 30)),
+            ("2026-10-15T14:00Z+30m", utc(14, 30)),
+            ("2026-10-15T14:00:00+02:00", utc(12, 0)),
+            ("2026-10-15 09:30-0500", utc(14, 30)),
+            (
+                "2026-10-15T14:00:00.250Z",
+                utc(14, 0) + Duration::milliseconds(250),
+            ),
+            ("1792065600000", utc(12, 0)),
+            ("yesterday-1h", utc(0, 0) - Duration::hours(25)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_time_arg(input, now()).unwrap(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parses_named_ranges() {
+        let cases = [
+            ("2h ago..now", (utc(10, 0), now())),
+            ("2026-10-15T14:00Z+30m", (utc(14, 0), utc(14, 30))),
+            ("2026-10-15T14:00Z-30m", (utc(13, 30), utc(14, 0))),
+            ("1792065600000..+1h", (utc(12, 0), utc(13, 0))),
+            ("today", (utc(0, 0), now())),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_time_range(input, now()).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(parse_duration("m").is_err());
+        assert!(parse_time_arg("next tuesday", now()).is_err());
+        assert!(parse_time_arg("today 25:00", now()).is_err());
+        assert!(parse_time_arg("2026-13-01", now()).is_err());
+        assert!(parse_time_range("2h ago..+1x", now()).is_err());
+        assert!(check_time_range("whenever").is_err());
+        assert!(DisplayTimezone::parse("Mars/Olympus_Mons").is_err());
+        assert!(DisplayTimezone::try_from("UTC+25".to_string()).is_err());
+        assert_eq!(
+            DisplayTimezone::try_from(" Europe/Berlin ".to_string()).unwrap(),
+            DisplayTimezone::Named(chrono_tz::Europe::Berlin)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use ratatui::prelude::{Color, Line, Span, Style};
+use ratatui::prelude::{Color, Line, Rect, Span, Style};
 
 pub fn format_json(value: &serde_json::Value, indent: usize) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
@@ -85,3 +85,50 @@ fn format_json_value(value: &serde_json::Value) -> Span<'static> {
         _ => Span::raw(value.to_string()),
     }
 }
+
+/// Returns a `width` x `height` rectangle centered in `area`, clamped to fit.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// e.g. `1.2 MB`
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// How long ago `millis` was, e.g. `45s ago`, `3h ago` or `12d ago`.
+pub fn format_age(millis: i64, now: i64) -> String {
+    let seconds = ((now - millis) / 1000).max(0);
+    match seconds {
+        0..60 => format!("{}s ago", seconds),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
+/// A short label for a log stream, e.g. `a1b2c3d4` for the Lambda stream
+/// `2024/01/01/[$LATEST]a1b2c3d4...`.
+pub fn short_stream_name(name: &str) -> &str {
+    let id = name.rsplit([']', '/']).next().unwrap_or(name);
+    let end = id.char_indices().nth(8).map_or(id.len(), |(i, _)| i);
+    &id[..end]
+}